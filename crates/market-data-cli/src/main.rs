@@ -3,11 +3,19 @@ use std::path::PathBuf;
 use anyhow::{Context, Result};
 use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
+use market_data_core::candle::Timeframe;
+use market_data_core::compact::PackPeriod;
+use market_data_core::config::Codec;
+use market_data_core::gaps::{GapAnalyzer, MarketSchedule};
+use market_data_core::precision::PriceRule;
+use market_data_core::remote;
 use market_data_core::store::CandleStore;
+use market_data_core::validation::{Severity, Validator};
 use market_data_providers::alpaca::AlpacaProvider;
 use market_data_providers::cboe::CboeProvider;
 use market_data_providers::provider::CandleProvider;
 use market_data_providers::yahoo::YahooProvider;
+use rust_decimal::Decimal;
 use tracing::{info, warn};
 
 #[derive(Parser)]
@@ -66,6 +74,93 @@ enum Commands {
         #[arg(short, long, value_delimiter = ',')]
         symbols: Option<Vec<String>>,
     },
+
+    /// Report missing bars against the expected session schedule
+    Gaps {
+        /// Symbols to check (all if omitted, comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+
+        /// First trading date (YYYY-MM-DD, defaults to the first stored date)
+        #[arg(long)]
+        start: Option<NaiveDate>,
+
+        /// Last trading date (YYYY-MM-DD, defaults to the last stored date)
+        #[arg(long)]
+        end: Option<NaiveDate>,
+
+        /// Also expect pre-market and after-hours bars
+        #[arg(long)]
+        extended: bool,
+
+        /// Check daily bars instead of 5-minute bars
+        #[arg(long)]
+        daily: bool,
+
+        /// Calendar file of holidays (YYYY-MM-DD) and early closes (YYYY-MM-DD HH:MM)
+        #[arg(long)]
+        holidays: Option<PathBuf>,
+
+        /// Longest run of missing bars reported as no trades rather than an outage
+        #[arg(long, default_value_t = 3)]
+        quiet_run: usize,
+    },
+
+    /// Write the index files used to serve the store over HTTP
+    Index,
+
+    /// Re-encode existing files with the store's writer settings
+    Rewrite {
+        /// Symbols to rewrite (all if omitted, comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+
+        /// Change and save the compression first: uncompressed, snappy, lz4, zstd, gzip
+        #[arg(long)]
+        compression: Option<String>,
+
+        /// Compression level for zstd (default 3) or gzip (default 6)
+        #[arg(long, requires = "compression")]
+        level: Option<i32>,
+
+        /// Round prices to this many decimal places from now on, for the
+        /// given symbols or else by default (saved in the store config)
+        #[arg(long)]
+        price_scale: Option<u32>,
+
+        /// Also round prices to a multiple of this tick size, e.g. 0.05
+        #[arg(long, requires = "price_scale")]
+        tick_size: Option<Decimal>,
+    },
+
+    /// Pack closed months or years of day files into single files
+    Compact {
+        /// Symbols to compact (all if omitted, comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+
+        /// Span of each pack: month, year
+        #[arg(long, default_value = "month")]
+        period: String,
+
+        /// Only pack periods that end before this date (YYYY-MM-DD, defaults to today)
+        #[arg(long)]
+        before: Option<NaiveDate>,
+    },
+
+    /// Split packed files back into one file per day (daily series stay in yearly packs)
+    Uncompact {
+        /// Symbols to uncompact (all if omitted, comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+    },
+
+    /// Re-stamp stored daily bars at the 9:30 ET open of their date
+    RestampDaily {
+        /// Daily-series symbols to migrate, e.g. VIX (comma-separated)
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        symbols: Vec<String>,
+    },
 }
 
 fn create_provider(name: &str) -> Result<Box<dyn CandleProvider>> {
@@ -120,7 +215,9 @@ async fn cmd_populate(
         let dates_to_fetch = if force {
             market_data_core::trading_calendar::weekdays(start, end)
         } else {
-            store.missing_dates(&symbol, start, end)
+            store
+                .try_missing_dates(&symbol, start, end)
+                .with_context(|| format!("failed to list stored dates for {symbol}"))?
         };
 
         if dates_to_fetch.is_empty() {
@@ -147,16 +244,26 @@ async fn cmd_populate(
                 Ok(day_groups) => {
                     let mut days_written = 0;
                     let mut total_candles = 0;
+                    let mut daily = Vec::new();
                     for (date, candles) in &day_groups {
                         if candles.is_empty() {
                             continue;
                         }
-                        store
-                            .write_day(&symbol, *date, candles)
-                            .with_context(|| format!("failed to write {symbol} {date}"))?;
+                        if provider.timeframe() == Timeframe::Daily {
+                            daily.extend_from_slice(candles);
+                        } else {
+                            store
+                                .write_day(&symbol, *date, candles)
+                                .with_context(|| format!("failed to write {symbol} {date}"))?;
+                        }
                         days_written += 1;
                         total_candles += candles.len();
                     }
+                    if !daily.is_empty() {
+                        store.write_daily(&symbol, &daily).with_context(|| {
+                            format!("failed to write {symbol} {range_start} to {range_end}")
+                        })?;
+                    }
                     info!(
                         "{symbol}: {range_start} to {range_end}: wrote {total_candles} candle(s) across {days_written} day(s)"
                     );
@@ -201,66 +308,199 @@ fn cmd_status(store: &CandleStore, symbol: Option<&str>) -> Result<()> {
 }
 
 fn cmd_validate(store: &CandleStore, symbols: Option<&[String]>) -> Result<()> {
-    let all_symbols = store.list_symbols().context("failed to list symbols")?;
-
-    let symbols_to_check: Vec<&str> = match symbols {
-        Some(list) => list.iter().map(|s| s.as_str()).collect(),
-        None => all_symbols.iter().map(|s| s.as_str()).collect(),
-    };
-
-    if symbols_to_check.is_empty() {
+    let symbols = selected_symbols(store, symbols)?;
+    if symbols.is_empty() {
         println!("No data to validate.");
         return Ok(());
     }
 
-    let mut issues = 0;
-
-    for sym in &symbols_to_check {
-        let dates = store
-            .list_dates(sym)
-            .with_context(|| format!("failed to list dates for {sym}"))?;
+    let report = Validator::default()
+        .validate(store, &symbols)
+        .context("failed to validate store")?;
+    for issue in &report.issues {
+        println!("{issue}");
+    }
 
-        for date in &dates {
-            match store.read_day(sym, *date) {
-                Ok(candles) => {
-                    if candles.is_empty() {
-                        println!("WARN: {sym} {date}: empty file");
-                        issues += 1;
-                        continue;
-                    }
+    if report.is_clean() {
+        println!("All {} file(s) valid.", report.days_checked);
+    } else {
+        println!(
+            "{} issue(s) in {} file(s): {} error(s), {} warning(s), {} info.",
+            report.issues.len(),
+            report.days_checked,
+            report.count(Severity::Error),
+            report.count(Severity::Warning),
+            report.count(Severity::Info)
+        );
+    }
 
-                    // Check timestamp ordering
-                    for i in 1..candles.len() {
-                        if candles[i].timestamp <= candles[i - 1].timestamp {
-                            println!(
-                                "WARN: {sym} {date}: timestamps not strictly ascending at index {i}"
-                            );
-                            issues += 1;
-                            break;
-                        }
-                    }
+    Ok(())
+}
 
-                    // Check volume
-                    let zero_volume = candles.iter().filter(|c| c.volume == 0).count();
-                    if zero_volume > 0 {
-                        println!("WARN: {sym} {date}: {zero_volume} candle(s) with zero volume");
-                        issues += 1;
-                    }
+fn cmd_gaps(
+    store: &CandleStore,
+    symbols: Option<&[String]>,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    analyzer: &GapAnalyzer,
+) -> Result<()> {
+    for sym in selected_symbols(store, symbols)? {
+        let Some((first, last)) = store
+            .date_range(&sym)
+            .with_context(|| format!("failed to list dates for {sym}"))?
+        else {
+            println!("{sym}: no data");
+            continue;
+        };
+        let report = analyzer
+            .analyze(store, &sym, start.unwrap_or(first), end.unwrap_or(last))
+            .with_context(|| format!("failed to analyze {sym}"))?;
+
+        for day in report.incomplete_days() {
+            println!(
+                "{sym} {}: {}/{} bars ({:.1}%)",
+                day.date,
+                day.present(),
+                day.expected(),
+                day.completeness()
+            );
+            for session in &day.sessions {
+                for gap in &session.gaps {
+                    println!("  {:?}: {gap}", session.session);
                 }
-                Err(e) => {
-                    println!("ERROR: {sym} {date}: failed to read: {e}");
-                    issues += 1;
+            }
+        }
+        println!(
+            "{sym}: {} trading day(s), {:.1}% complete, {} day(s) with outages",
+            report.days.len(),
+            report.completeness(),
+            report.outage_days()
+        );
+    }
+    Ok(())
+}
+
+fn cmd_index(store: &CandleStore) -> Result<()> {
+    let index = remote::write_index(store).context("failed to write index")?;
+    println!("Indexed {} symbol(s).", index.symbols.len());
+    Ok(())
+}
+
+fn parse_codec(name: &str, level: Option<i32>) -> Result<Codec> {
+    Ok(match name {
+        "uncompressed" => Codec::Uncompressed,
+        "snappy" => Codec::Snappy,
+        "lz4" => Codec::Lz4,
+        "zstd" => Codec::Zstd {
+            level: level.unwrap_or(3),
+        },
+        "gzip" => Codec::Gzip {
+            level: level
+                .unwrap_or(6)
+                .try_into()
+                .context("gzip level must be 0-9")?,
+        },
+        other => anyhow::bail!(
+            "unknown compression: {other}. Expected: uncompressed, snappy, lz4, zstd, gzip"
+        ),
+    })
+}
+
+fn cmd_rewrite(
+    store: &CandleStore,
+    symbols: Option<&[String]>,
+    compression: Option<Codec>,
+    price_rule: Option<PriceRule>,
+) -> Result<()> {
+    let mut store = store.clone();
+    let mut config = store.config().clone();
+    if let Some(codec) = compression {
+        config.compression = codec;
+    }
+    if let Some(rule) = price_rule {
+        let rules = config.prices.get_or_insert_default();
+        match symbols {
+            Some(list) => {
+                for sym in list {
+                    rules.symbols.insert(sym.to_uppercase(), rule);
                 }
             }
+            None => rules.default = rule,
         }
     }
+    if &config != store.config() {
+        store = store.with_config(config);
+        store.save_config().context("failed to save store config")?;
+        info!(config = ?store.config(), "saved store config");
+    }
 
-    if issues == 0 {
-        println!("All files valid.");
-    } else {
-        println!("{issues} issue(s) found.");
+    let symbols = selected_symbols(&store, symbols)?;
+
+    let mut files = 0;
+    for sym in &symbols {
+        let count = store
+            .rewrite_symbol(sym)
+            .with_context(|| format!("failed to rewrite {sym}"))?;
+        info!(symbol = %sym, files = count, "rewrote");
+        files += count;
+    }
+    println!("Rewrote {files} file(s) for {} symbol(s).", symbols.len());
+    Ok(())
+}
+
+fn parse_period(name: &str) -> Result<PackPeriod> {
+    match name {
+        "month" => Ok(PackPeriod::Month),
+        "year" => Ok(PackPeriod::Year),
+        other => anyhow::bail!("unknown period: {other}. Expected: month, year"),
     }
+}
+
+fn selected_symbols(store: &CandleStore, symbols: Option<&[String]>) -> Result<Vec<String>> {
+    match symbols {
+        Some(list) => Ok(list.iter().map(|s| s.to_uppercase()).collect()),
+        None => store.list_symbols().context("failed to list symbols"),
+    }
+}
 
+fn cmd_compact(
+    store: &CandleStore,
+    symbols: Option<&[String]>,
+    period: PackPeriod,
+    before: NaiveDate,
+) -> Result<()> {
+    for sym in selected_symbols(store, symbols)? {
+        let report = store
+            .compact(&sym, period, before)
+            .with_context(|| format!("failed to compact {sym}"))?;
+        println!(
+            "{sym}: packed {} day file(s) into {} file(s)",
+            report.day_files, report.packs_written
+        );
+    }
+    Ok(())
+}
+
+fn cmd_uncompact(store: &CandleStore, symbols: Option<&[String]>) -> Result<()> {
+    for sym in selected_symbols(store, symbols)? {
+        let report = store
+            .uncompact(&sym)
+            .with_context(|| format!("failed to uncompact {sym}"))?;
+        println!(
+            "{sym}: split {} pack(s) into {} day file(s)",
+            report.packs_removed, report.day_files
+        );
+    }
+    Ok(())
+}
+
+fn cmd_restamp_daily(store: &CandleStore, symbols: &[String]) -> Result<()> {
+    for sym in selected_symbols(store, Some(symbols))? {
+        let count = store
+            .restamp_daily(&sym)
+            .with_context(|| format!("failed to re-stamp {sym}"))?;
+        println!("{sym}: re-stamped {count} daily bar(s)");
+    }
     Ok(())
 }
 
@@ -275,7 +515,7 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    let store = CandleStore::new(&cli.data_dir);
+    let store = CandleStore::open(&cli.data_dir).context("failed to open store")?;
 
     match &cli.command {
         Commands::Populate {
@@ -295,6 +535,69 @@ async fn main() -> Result<()> {
         Commands::Validate { symbols } => {
             cmd_validate(&store, symbols.as_deref())?;
         }
+        Commands::Gaps {
+            symbols,
+            start,
+            end,
+            extended,
+            daily,
+            holidays,
+            quiet_run,
+        } => {
+            let mut schedule = if *extended {
+                MarketSchedule::extended()
+            } else {
+                MarketSchedule::default()
+            };
+            if let Some(path) = holidays {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                schedule = schedule
+                    .with_calendar(&text)
+                    .with_context(|| format!("invalid calendar {}", path.display()))?;
+            }
+            let timeframe = if *daily {
+                Timeframe::Daily
+            } else {
+                Timeframe::FiveMinute
+            };
+            let analyzer = GapAnalyzer::new(schedule, timeframe).with_max_quiet_run(*quiet_run);
+            cmd_gaps(&store, symbols.as_deref(), *start, *end, &analyzer)?;
+        }
+        Commands::Index => {
+            cmd_index(&store)?;
+        }
+        Commands::Rewrite {
+            symbols,
+            compression,
+            level,
+            price_scale,
+            tick_size,
+        } => {
+            let codec = compression
+                .as_deref()
+                .map(|name| parse_codec(name, *level))
+                .transpose()?;
+            let price_rule = price_scale.map(|scale| PriceRule {
+                scale,
+                tick_size: *tick_size,
+            });
+            cmd_rewrite(&store, symbols.as_deref(), codec, price_rule)?;
+        }
+        Commands::Compact {
+            symbols,
+            period,
+            before,
+        } => {
+            let before = before.unwrap_or_else(|| chrono::Utc::now().date_naive());
+            cmd_compact(&store, symbols.as_deref(), parse_period(period)?, before)?;
+        }
+        Commands::Uncompact { symbols } => {
+            cmd_uncompact(&store, symbols.as_deref())?;
+        }
+        Commands::RestampDaily { symbols } => {
+            cmd_restamp_daily(&store, symbols)?;
+        }
     }
 
     Ok(())
@@ -372,6 +675,180 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_index() {
+        let cli = Cli::try_parse_from(["market-data", "index"]).unwrap();
+        assert!(matches!(cli.command, Commands::Index));
+    }
+
+    #[test]
+    fn parse_rewrite_args() {
+        let cli = Cli::try_parse_from([
+            "market-data",
+            "rewrite",
+            "-s",
+            "AAPL",
+            "--compression",
+            "zstd",
+            "--level",
+            "9",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Rewrite {
+                symbols,
+                compression,
+                level,
+                ..
+            } => {
+                assert_eq!(symbols, Some(vec!["AAPL".to_string()]));
+                assert_eq!(
+                    parse_codec(compression.as_deref().unwrap(), level).unwrap(),
+                    Codec::Zstd { level: 9 }
+                );
+            }
+            _ => panic!("expected Rewrite command"),
+        }
+        assert!(parse_codec("brotli", None).is_err());
+        assert!(Cli::try_parse_from(["market-data", "rewrite", "--level", "9"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "market-data",
+            "rewrite",
+            "--price-scale",
+            "2",
+            "--tick-size",
+            "0.05",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Rewrite {
+                price_scale: Some(2),
+                tick_size: Some(tick),
+                ..
+            } if tick == Decimal::new(5, 2)
+        ));
+        assert!(Cli::try_parse_from(["market-data", "rewrite", "--tick-size", "0.05"]).is_err());
+    }
+
+    #[test]
+    fn parse_compact_args() {
+        let cli = Cli::try_parse_from([
+            "market-data",
+            "compact",
+            "-s",
+            "AAPL,MSFT",
+            "--period",
+            "year",
+            "--before",
+            "2025-01-01",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Compact {
+                symbols,
+                period,
+                before,
+            } => {
+                assert_eq!(symbols, Some(vec!["AAPL".to_string(), "MSFT".to_string()]));
+                assert_eq!(parse_period(&period).unwrap(), PackPeriod::Year);
+                assert_eq!(before, NaiveDate::from_ymd_opt(2025, 1, 1));
+            }
+            _ => panic!("expected Compact command"),
+        }
+
+        let cli = Cli::try_parse_from(["market-data", "compact"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Compact { ref period, before: None, .. } if period == "month"
+        ));
+        assert!(parse_period("week").is_err());
+
+        let cli = Cli::try_parse_from(["market-data", "uncompact", "-s", "VIX"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Uncompact { symbols: Some(_) }
+        ));
+    }
+
+    #[test]
+    fn rewrite_matches_price_rules_case_insensitively() {
+        use chrono::TimeZone;
+        use market_data_core::backend::MemoryBackend;
+        use market_data_core::candle::Candle;
+
+        let store = CandleStore::with_backend(std::sync::Arc::new(MemoryBackend::new()));
+        let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        let price = Decimal::new(150_567, 3);
+        let candle = Candle {
+            timestamp: chrono::Utc
+                .with_ymd_and_hms(2025, 1, 15, 14, 30, 0)
+                .unwrap(),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        };
+        store.write_day("AAPL", date, &[candle]).unwrap();
+
+        let symbols = ["aapl".to_string()];
+        cmd_rewrite(&store, Some(&symbols), None, Some(PriceRule::scale(2))).unwrap();
+
+        assert_eq!(
+            store.read_day("AAPL", date).unwrap()[0].close,
+            Decimal::new(15_057, 2)
+        );
+    }
+
+    #[test]
+    fn parse_restamp_daily_args() {
+        let cli = Cli::try_parse_from(["market-data", "restamp-daily", "-s", "VIX,vvix"]).unwrap();
+        match cli.command {
+            Commands::RestampDaily { symbols } => assert_eq!(symbols, vec!["VIX", "vvix"]),
+            _ => panic!("expected RestampDaily command"),
+        }
+        assert!(Cli::try_parse_from(["market-data", "restamp-daily"]).is_err());
+    }
+
+    #[test]
+    fn parse_gaps_args() {
+        let cli = Cli::try_parse_from([
+            "market-data",
+            "gaps",
+            "-s",
+            "AAPL",
+            "--start",
+            "2025-01-02",
+            "--extended",
+            "--holidays",
+            "holidays.txt",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Gaps {
+                symbols,
+                start,
+                end,
+                extended,
+                daily,
+                holidays,
+                quiet_run,
+            } => {
+                assert_eq!(symbols, Some(vec!["AAPL".to_string()]));
+                assert_eq!(start, NaiveDate::from_ymd_opt(2025, 1, 2));
+                assert!(end.is_none());
+                assert!(extended && !daily);
+                assert_eq!(holidays, Some(PathBuf::from("holidays.txt")));
+                assert_eq!(quiet_run, 3);
+            }
+            _ => panic!("expected Gaps command"),
+        }
+    }
+
     #[test]
     fn parse_populate_force() {
         let cli = Cli::try_parse_from([
@@ -0,0 +1,653 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use tokio::sync::mpsc;
+
+use crate::candle::Candle;
+use crate::error::MarketDataError;
+use crate::query::{Query, ReadReport};
+use crate::session::Session;
+use crate::store::{CandleStore, DayStats, SnapshotRow};
+use crate::trading_calendar;
+
+/// Number of day files read concurrently by range reads.
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Async façade over [`CandleStore`] for tokio consumers.
+///
+/// Blocking file I/O runs on tokio's blocking thread pool, and range reads
+/// decode several day files concurrently. Enabled with the `async` feature.
+#[derive(Debug, Clone)]
+pub struct AsyncCandleStore {
+    store: CandleStore,
+    concurrency: usize,
+}
+
+impl AsyncCandleStore {
+    /// Create a store rooted at the given directory.
+    /// The `data/` subdirectory is used automatically.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self::from_store(CandleStore::new(root))
+    }
+
+    /// Create a store pointing directly at the data directory (no `data/` suffix).
+    pub fn from_data_dir(data_dir: impl AsRef<Path>) -> Self {
+        Self::from_store(CandleStore::from_data_dir(data_dir))
+    }
+
+    /// Wrap an existing store, keeping its cache and configuration.
+    pub fn from_store(store: CandleStore) -> Self {
+        Self {
+            store,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Set how many day files range reads decode at once.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// The underlying blocking store.
+    pub fn blocking(&self) -> &CandleStore {
+        &self.store
+    }
+
+    /// Run a blocking store operation on the blocking thread pool.
+    async fn run<T, F>(&self, f: F) -> Result<T, MarketDataError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&CandleStore) -> Result<T, MarketDataError> + Send + 'static,
+    {
+        let store = self.store.clone();
+        tokio::task::spawn_blocking(move || f(&store))
+            .await
+            .map_err(|e| MarketDataError::Io(std::io::Error::other(e)))?
+    }
+
+    pub async fn has_data(&self, symbol: &str, date: NaiveDate) -> Result<bool, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| Ok(s.has_data(&symbol, date))).await
+    }
+
+    pub async fn missing_dates(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<NaiveDate>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| Ok(s.missing_dates(&symbol, start, end)))
+            .await
+    }
+
+    pub async fn write_day(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        candles: Vec<Candle>,
+    ) -> Result<(), MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.write_day(&symbol, date, &candles))
+            .await
+    }
+
+    /// Write daily bars into year packs; see [`CandleStore::write_daily`].
+    pub async fn write_daily(
+        &self,
+        symbol: &str,
+        candles: Vec<Candle>,
+    ) -> Result<(), MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.write_daily(&symbol, &candles)).await
+    }
+
+    pub async fn read_day(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_day(&symbol, date)).await
+    }
+
+    /// Read candles for a symbol across a date range (inclusive), decoding
+    /// day files concurrently. Returns candles sorted by timestamp.
+    pub async fn read_range(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let days: Vec<Vec<Candle>> = self.day_stream(symbol, start, end).try_collect().await?;
+        let mut candles: Vec<Candle> = days.into_iter().flatten().collect();
+        candles.sort_by_key(|c| c.timestamp);
+        Ok(candles)
+    }
+
+    /// Read candles filtered by session type.
+    pub async fn read_range_session(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        session: Session,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_range_session(&symbol, start, end, session))
+            .await
+    }
+
+    /// Read candles within a UTC time window on one date; see
+    /// [`CandleStore::read_time_range`].
+    pub async fn read_time_range(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_time_range(&symbol, date, start_time, end_time))
+            .await
+    }
+
+    /// Read candles within an Eastern Time window on one exchange date; see
+    /// [`CandleStore::read_time_range_et`].
+    pub async fn read_time_range_et(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_time_range_et(&symbol, date, start_time, end_time))
+            .await
+    }
+
+    /// Read candles whose exchange-local time of day falls within
+    /// `[start_time, end_time]`; see [`CandleStore::read_range_time_of_day`].
+    pub async fn read_range_time_of_day(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_range_time_of_day(&symbol, start, end, start_time, end_time))
+            .await
+    }
+
+    /// Like [`AsyncCandleStore::read_range`], but skips and reports unreadable
+    /// day files; see [`CandleStore::read_range_lenient`].
+    pub async fn read_range_lenient(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<(Vec<Candle>, ReadReport), MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_range_lenient(&symbol, start, end))
+            .await
+    }
+
+    /// The `n` candles before `before`, oldest first; see
+    /// [`CandleStore::read_last_n`].
+    pub async fn read_last_n(
+        &self,
+        symbol: &str,
+        before: DateTime<Utc>,
+        n: usize,
+        session: Option<Session>,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_last_n(&symbol, before, n, session))
+            .await
+    }
+
+    /// The `n` candles after `after`; see [`CandleStore::read_next_n`].
+    pub async fn read_next_n(
+        &self,
+        symbol: &str,
+        after: DateTime<Utc>,
+        n: usize,
+        session: Option<Session>,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.read_next_n(&symbol, after, n, session))
+            .await
+    }
+
+    /// Latest candle at or before `at`, no older than `max_staleness`; see
+    /// [`CandleStore::candle_as_of`].
+    pub async fn candle_as_of(
+        &self,
+        symbol: &str,
+        at: DateTime<Utc>,
+        max_staleness: Duration,
+    ) -> Result<Option<Candle>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.candle_as_of(&symbol, at, max_staleness))
+            .await
+    }
+
+    /// Latest candle of each symbol at `at`; see [`CandleStore::snapshot`].
+    pub async fn snapshot(
+        &self,
+        symbols: Vec<String>,
+        at: DateTime<Utc>,
+        max_staleness: Duration,
+    ) -> Result<Vec<SnapshotRow>, MarketDataError> {
+        self.run(move |s| s.snapshot(&symbols, at, max_staleness))
+            .await
+    }
+
+    /// Row count, time bounds and file size of one day; see [`CandleStore::day_stats`].
+    pub async fn day_stats(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+    ) -> Result<DayStats, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.day_stats(&symbol, date)).await
+    }
+
+    /// [`AsyncCandleStore::day_stats`] for every stored day of a symbol.
+    pub async fn symbol_stats(&self, symbol: &str) -> Result<Vec<DayStats>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.symbol_stats(&symbol)).await
+    }
+
+    pub async fn list_symbols(&self) -> Result<Vec<String>, MarketDataError> {
+        self.run(|s| s.list_symbols()).await
+    }
+
+    pub async fn list_dates(&self, symbol: &str) -> Result<Vec<NaiveDate>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.list_dates(&symbol)).await
+    }
+
+    pub async fn date_range(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<(NaiveDate, NaiveDate)>, MarketDataError> {
+        let symbol = symbol.to_string();
+        self.run(move |s| s.date_range(&symbol)).await
+    }
+
+    /// Stream the candles of each day file in a date range, in date order,
+    /// with up to `concurrency` files being decoded ahead of the consumer.
+    pub fn day_stream(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Stream<Item = Result<Vec<Candle>, MarketDataError>> + Send + 'static {
+        let this = self.clone();
+        let symbol = symbol.to_string();
+        let concurrency = self.concurrency;
+
+        // Resolve every day's file once, so each pack is fetched once per
+        // stream rather than once per day
+        let index = {
+            let this = this.clone();
+            let symbol = symbol.clone();
+            async move {
+                this.run(move |s| s.index_days(&symbol, Some((start, end))))
+                    .await
+                    .map(Arc::new)
+            }
+        };
+
+        stream::once(index)
+            .map_ok(move |index| {
+                let dates: Vec<NaiveDate> = index
+                    .dates()
+                    .filter(|d| trading_calendar::is_weekday(*d))
+                    .collect();
+                let this = this.clone();
+                let symbol = symbol.clone();
+                stream::iter(dates)
+                    .map(move |date| {
+                        let this = this.clone();
+                        let symbol = symbol.clone();
+                        let index = index.clone();
+                        async move {
+                            this.run(move |s| match s.read_day_indexed(&symbol, date, &index) {
+                                Err(MarketDataError::NoData { .. }) => Ok(Vec::new()),
+                                day => day,
+                            })
+                            .await
+                        }
+                    })
+                    .buffered(concurrency)
+            })
+            .try_flatten()
+            .try_filter(|day| futures::future::ready(!day.is_empty()))
+    }
+
+    /// Run a [`Query`] on the blocking pool and stream its results.
+    ///
+    /// `build` configures the query, e.g.
+    /// `store.query_stream("AAPL", |q| q.session(Session::Regular).descending())`.
+    /// Candles are produced lazily; dropping the stream stops the scan.
+    pub fn query_stream<F>(
+        &self,
+        symbol: &str,
+        build: F,
+    ) -> impl Stream<Item = Result<Candle, MarketDataError>> + Send + 'static
+    where
+        F: for<'a> FnOnce(Query<'a>) -> Query<'a> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(self.concurrency * 64);
+        let store = self.store.clone();
+        let symbol = symbol.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let candles = match build(store.query(&symbol)).iter() {
+                Ok(iter) => iter,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(e));
+                    return;
+                }
+            };
+            for candle in candles {
+                if tx.blocking_send(candle).is_err() {
+                    break;
+                }
+            }
+        });
+
+        stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MemoryBackend, ObjectMeta, StorageBackend};
+    use crate::candle::Timeframe;
+    use bytes::Bytes;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn make_candles_for_date(day: u32) -> Vec<Candle> {
+        [(14, 30), (22, 0)]
+            .into_iter()
+            .map(|(hour, min)| Candle {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, day, hour, min, 0).unwrap(),
+                open: dec!(150.00),
+                high: dec!(151.00),
+                low: dec!(149.00),
+                close: dec!(150.50),
+                volume: 1000,
+                vwap: None,
+                trade_count: None,
+            })
+            .collect()
+    }
+
+    async fn populated_store(dir: &Path) -> AsyncCandleStore {
+        let store = AsyncCandleStore::new(dir).with_concurrency(2);
+        for day in 13..=17 {
+            store
+                .write_day("AAPL", date(2025, 1, day), make_candles_for_date(day))
+                .await
+                .unwrap();
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn read_range_concurrently_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path()).await;
+
+        let candles = store
+            .read_range("AAPL", date(2025, 1, 13), date(2025, 1, 19))
+            .await
+            .unwrap();
+        assert_eq!(candles.len(), 10);
+        assert!(candles.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[tokio::test]
+    async fn listing_and_day_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path()).await;
+
+        assert_eq!(store.list_symbols().await.unwrap(), vec!["AAPL"]);
+        assert_eq!(store.list_dates("AAPL").await.unwrap().len(), 5);
+        assert_eq!(
+            store.date_range("AAPL").await.unwrap(),
+            Some((date(2025, 1, 13), date(2025, 1, 17)))
+        );
+        assert_eq!(
+            store.read_day("AAPL", date(2025, 1, 15)).await.unwrap(),
+            make_candles_for_date(15)
+        );
+        assert!(store.has_data("AAPL", date(2025, 1, 15)).await.unwrap());
+        assert_eq!(
+            store
+                .missing_dates("AAPL", date(2025, 1, 13), date(2025, 1, 20))
+                .await
+                .unwrap(),
+            vec![date(2025, 1, 20)]
+        );
+        assert_eq!(
+            store
+                .read_range_session(
+                    "AAPL",
+                    date(2025, 1, 13),
+                    date(2025, 1, 17),
+                    Session::Regular
+                )
+                .await
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+
+    #[tokio::test]
+    async fn lookback_and_window_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path()).await;
+        let at = Utc.with_ymd_and_hms(2025, 1, 16, 0, 0, 0).unwrap();
+
+        let last = store.read_last_n("AAPL", at, 3, None).await.unwrap();
+        assert_eq!(
+            last,
+            store.blocking().read_last_n("AAPL", at, 3, None).unwrap()
+        );
+        assert_eq!(last.len(), 3);
+        let next = store
+            .read_next_n("AAPL", at, 2, Some(Session::Regular))
+            .await
+            .unwrap();
+        assert_eq!(next.len(), 2);
+        assert_eq!(
+            store
+                .candle_as_of("AAPL", at, Duration::hours(3))
+                .await
+                .unwrap()
+                .map(|c| c.timestamp),
+            Some(Utc.with_ymd_and_hms(2025, 1, 15, 22, 0, 0).unwrap())
+        );
+
+        let open = NaiveTime::from_hms_opt(9, 30, 0).unwrap();
+        assert_eq!(
+            store
+                .read_range_time_of_day("AAPL", date(2025, 1, 13), date(2025, 1, 17), open, open)
+                .await
+                .unwrap()
+                .len(),
+            5
+        );
+        let (candles, report) = store
+            .read_range_lenient("AAPL", date(2025, 1, 13), date(2025, 1, 17))
+            .await
+            .unwrap();
+        assert_eq!(candles.len(), 10);
+        assert!(report.is_complete());
+    }
+
+    #[tokio::test]
+    async fn day_stream_preserves_date_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path()).await;
+
+        let days: Vec<Vec<Candle>> = store
+            .day_stream("AAPL", date(2025, 1, 10), date(2025, 1, 17))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(days.len(), 5);
+        assert!(
+            days.windows(2)
+                .all(|w| w[0][0].timestamp < w[1][0].timestamp)
+        );
+    }
+
+    /// Backend that counts fetches.
+    #[derive(Debug, Default)]
+    struct CountingGets {
+        inner: MemoryBackend,
+        gets: AtomicUsize,
+    }
+
+    impl StorageBackend for CountingGets {
+        fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+            self.gets.fetch_add(1, Ordering::Relaxed);
+            self.inner.get(key)
+        }
+
+        fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+            self.inner.head(key)
+        }
+
+        fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+            self.inner.put(key, data)
+        }
+
+        fn delete(&self, key: &str) -> Result<(), MarketDataError> {
+            self.inner.delete(key)
+        }
+
+        fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            self.inner.list(prefix)
+        }
+
+        fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            self.inner.list_prefixes(prefix)
+        }
+    }
+
+    #[tokio::test]
+    async fn range_reads_fetch_each_pack_once() {
+        let backend = Arc::new(CountingGets::default());
+        let store = AsyncCandleStore::from_store(CandleStore::with_backend(backend.clone()))
+            .with_concurrency(2);
+        let daily: Vec<Candle> = (13..=17)
+            .map(|day| Candle {
+                timestamp: Timeframe::session_open(date(2025, 1, day)),
+                ..make_candles_for_date(day)[0].clone()
+            })
+            .collect();
+        store.write_daily("VIX", daily.clone()).await.unwrap();
+
+        let before = backend.gets.load(Ordering::Relaxed);
+        let days: Vec<Vec<Candle>> = store
+            .day_stream("VIX", date(2025, 1, 10), date(2025, 1, 19))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(days.concat(), daily);
+        assert_eq!(backend.gets.load(Ordering::Relaxed) - before, 1);
+
+        let before = backend.gets.load(Ordering::Relaxed);
+        assert_eq!(
+            store
+                .read_range("VIX", date(2025, 1, 1), date(2025, 1, 31))
+                .await
+                .unwrap(),
+            daily
+        );
+        assert_eq!(backend.gets.load(Ordering::Relaxed) - before, 1);
+    }
+
+    #[tokio::test]
+    async fn time_window_and_stats_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path()).await;
+        let blocking = store.blocking();
+        let d = date(2025, 1, 15);
+        let (from, to) = (
+            NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+        );
+
+        assert_eq!(
+            store.read_time_range("AAPL", d, from, to).await.unwrap(),
+            blocking.read_time_range("AAPL", d, from, to).unwrap()
+        );
+        let (open, close) = (
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        );
+        let et = store
+            .read_time_range_et("AAPL", d, open, close)
+            .await
+            .unwrap();
+        assert_eq!(et.len(), 2);
+        assert_eq!(
+            et,
+            blocking.read_time_range_et("AAPL", d, open, close).unwrap()
+        );
+
+        let at = Utc.with_ymd_and_hms(2025, 1, 16, 0, 0, 0).unwrap();
+        let symbols = vec!["AAPL".to_string(), "MSFT".to_string()];
+        assert_eq!(
+            store
+                .snapshot(symbols.clone(), at, Duration::days(1))
+                .await
+                .unwrap(),
+            blocking.snapshot(&symbols, at, Duration::days(1)).unwrap()
+        );
+
+        assert_eq!(store.day_stats("AAPL", d).await.unwrap().row_count, 2);
+        assert_eq!(store.symbol_stats("AAPL").await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn query_stream_yields_query_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path()).await;
+
+        let candles: Vec<Candle> = store
+            .query_stream("AAPL", |q| {
+                q.session(Session::AfterHours).limit(3).descending()
+            })
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(candles.len(), 3);
+        assert_eq!(
+            candles[0].timestamp,
+            Utc.with_ymd_and_hms(2025, 1, 17, 22, 0, 0).unwrap()
+        );
+    }
+}
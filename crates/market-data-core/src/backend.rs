@@ -0,0 +1,579 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use bytes::Bytes;
+
+use crate::error::MarketDataError;
+
+/// Size and modification time of a stored object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectMeta {
+    pub size: u64,
+    pub last_modified: Option<SystemTime>,
+}
+
+/// Blob storage underneath a [`CandleStore`](crate::store::CandleStore).
+///
+/// Objects are addressed by `/`-separated keys relative to the store root,
+/// e.g. `AAPL/2025/01/2025-01-15.parquet`. Implementations must be safe to
+/// share between threads; all calls block until the operation completes.
+pub trait StorageBackend: Send + Sync + Debug {
+    /// Fetch an object, or `None` if it does not exist.
+    fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError>;
+
+    /// Fetch an object's metadata, or `None` if it does not exist.
+    fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError>;
+
+    /// Store an object, replacing any existing one. Readers never observe a
+    /// partially written object.
+    fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError>;
+
+    /// Remove an object. Deleting a missing object is not an error.
+    fn delete(&self, key: &str) -> Result<(), MarketDataError>;
+
+    /// All keys under `prefix` at any depth, sorted.
+    fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError>;
+
+    /// Names of the immediate "directories" under `prefix`, sorted.
+    /// `list_prefixes("")` on a candle store yields its symbols.
+    fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError>;
+}
+
+/// Join a directory-style prefix and a child name into a key.
+fn join_key(prefix: &str, name: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}/{name}")
+    }
+}
+
+/// Local filesystem storage; keys map to paths under `root`.
+#[derive(Debug, Clone)]
+pub struct LocalBackend {
+    root: PathBuf,
+}
+
+impl LocalBackend {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Filesystem path of a key.
+    pub fn path(&self, key: &str) -> PathBuf {
+        key.split('/')
+            .filter(|part| !part.is_empty())
+            .fold(self.root.clone(), |path, part| path.join(part))
+    }
+
+    fn walk(&self, dir: &Path, key: &str, keys: &mut Vec<String>) -> Result<(), MarketDataError> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            // Skip in-flight temporary files from `put`
+            if name.starts_with('.') {
+                continue;
+            }
+            let child = join_key(key, &name);
+            if entry.file_type()?.is_dir() {
+                self.walk(&entry.path(), &child, keys)?;
+            } else {
+                keys.push(child);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StorageBackend for LocalBackend {
+    fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+        match std::fs::read(self.path(key)) {
+            Ok(data) => Ok(Some(Bytes::from(data))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+        match std::fs::metadata(self.path(key)) {
+            Ok(meta) if meta.is_file() => Ok(Some(ObjectMeta {
+                size: meta.len(),
+                last_modified: meta.modified().ok(),
+            })),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+        let path = self.path(key);
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return Err(MarketDataError::Storage(format!("invalid key: {key:?}")));
+        };
+        std::fs::create_dir_all(parent)?;
+
+        // Write beside the target and rename so readers never see a partial file
+        static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+        let tmp = parent.join(format!(
+            ".{}.{}.{}.tmp",
+            name.to_string_lossy(),
+            std::process::id(),
+            NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&tmp, &data)?;
+        std::fs::rename(&tmp, &path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&tmp);
+        })?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), MarketDataError> {
+        match std::fs::remove_file(self.path(key)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        let prefix = prefix.trim_end_matches('/');
+        let mut keys = Vec::new();
+        self.walk(&self.path(prefix), prefix, &mut keys)?;
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        let entries = match std::fs::read_dir(self.path(prefix)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir()
+                && let Some(name) = entry.file_name().to_str()
+            {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+/// In-memory storage, mainly for tests.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    objects: RwLock<BTreeMap<String, (Bytes, SystemTime)>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+        let objects = self.objects.read().unwrap_or_else(|e| e.into_inner());
+        Ok(objects.get(key).map(|(data, _)| data.clone()))
+    }
+
+    fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+        let objects = self.objects.read().unwrap_or_else(|e| e.into_inner());
+        Ok(objects.get(key).map(|(data, modified)| ObjectMeta {
+            size: data.len() as u64,
+            last_modified: Some(*modified),
+        }))
+    }
+
+    fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+        let mut objects = self.objects.write().unwrap_or_else(|e| e.into_inner());
+        objects.insert(key.to_string(), (data, SystemTime::now()));
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), MarketDataError> {
+        let mut objects = self.objects.write().unwrap_or_else(|e| e.into_inner());
+        objects.remove(key);
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        let dir = join_key(prefix, "");
+        let objects = self.objects.read().unwrap_or_else(|e| e.into_inner());
+        Ok(objects
+            .range(dir.clone()..)
+            .take_while(|(key, _)| key.starts_with(&dir))
+            .map(|(key, _)| key.clone())
+            .collect())
+    }
+
+    fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        let dir = join_key(prefix, "");
+        let mut names: Vec<String> = self
+            .list(prefix)?
+            .iter()
+            .filter_map(|key| key[dir.len()..].split_once('/'))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+#[cfg(feature = "object-store")]
+pub use self::object_store_backend::ObjectStoreBackend;
+
+#[cfg(feature = "object-store")]
+mod object_store_backend {
+    use std::future::Future;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    use futures::TryStreamExt;
+    use object_store::aws::AmazonS3Builder;
+    use object_store::path::Path as ObjectPath;
+    use object_store::{ObjectStore, PutPayload};
+    use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+
+    use super::*;
+
+    /// Runtime shared by every backend. It lives for the whole process, so a
+    /// backend can be dropped anywhere, including on an async worker thread.
+    fn shared_runtime() -> Result<&'static Runtime, MarketDataError> {
+        static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+        static INIT: Mutex<()> = Mutex::new(());
+
+        if let Some(runtime) = RUNTIME.get() {
+            return Ok(runtime);
+        }
+        // Build under a lock: a losing runtime would be dropped, which panics
+        // when the caller is itself inside a runtime
+        let _guard = INIT.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(runtime) = RUNTIME.get() {
+            return Ok(runtime);
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("market-data-object-store")
+            .enable_all()
+            .build()?;
+        Ok(RUNTIME.get_or_init(|| runtime))
+    }
+
+    /// Storage in an object store such as S3 or an S3-compatible server
+    /// (MinIO, Ceph, R2). Enabled with the `object-store` feature.
+    ///
+    /// Calls block on a small tokio runtime shared by all backends. They may
+    /// be made from inside an async context, but block a worker thread while
+    /// they run; prefer `AsyncCandleStore` there.
+    #[derive(Debug)]
+    pub struct ObjectStoreBackend {
+        store: Arc<dyn ObjectStore>,
+        prefix: String,
+        runtime: &'static Runtime,
+    }
+
+    impl ObjectStoreBackend {
+        /// Wrap any [`ObjectStore`], storing keys under `prefix` (may be empty).
+        pub fn new(store: Arc<dyn ObjectStore>, prefix: &str) -> Result<Self, MarketDataError> {
+            Ok(Self {
+                store,
+                prefix: prefix.trim_matches('/').to_string(),
+                runtime: shared_runtime()?,
+            })
+        }
+
+        /// Connect to an S3-compatible endpoint with static credentials,
+        /// e.g. `http://localhost:9000` for a local MinIO.
+        pub fn s3_compatible(
+            endpoint: &str,
+            bucket: &str,
+            access_key_id: &str,
+            secret_access_key: &str,
+            prefix: &str,
+        ) -> Result<Self, MarketDataError> {
+            let store = AmazonS3Builder::new()
+                .with_endpoint(endpoint)
+                .with_bucket_name(bucket)
+                .with_access_key_id(access_key_id)
+                .with_secret_access_key(secret_access_key)
+                .with_region("us-east-1")
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_virtual_hosted_style_request(false)
+                .build()
+                .map_err(storage_error)?;
+            Self::new(Arc::new(store), prefix)
+        }
+
+        /// Connect to an S3 bucket using credentials and region from the
+        /// standard `AWS_*` environment variables.
+        pub fn s3_from_env(bucket: &str, prefix: &str) -> Result<Self, MarketDataError> {
+            let store = AmazonS3Builder::from_env()
+                .with_bucket_name(bucket)
+                .build()
+                .map_err(storage_error)?;
+            Self::new(Arc::new(store), prefix)
+        }
+
+        fn location(&self, key: &str) -> ObjectPath {
+            ObjectPath::from(join_key(&self.prefix, key.trim_matches('/')))
+        }
+
+        /// Strip the backend prefix from an object path.
+        fn key(&self, location: &ObjectPath) -> String {
+            let path = location.as_ref();
+            if self.prefix.is_empty() {
+                path.to_string()
+            } else {
+                path.strip_prefix(&self.prefix)
+                    .unwrap_or(path)
+                    .trim_start_matches('/')
+                    .to_string()
+            }
+        }
+
+        /// Run a future to completion on the shared runtime, from either
+        /// synchronous code or a task on another runtime.
+        fn block_on<F>(&self, future: F) -> F::Output
+        where
+            F: Future + Send,
+            F::Output: Send,
+        {
+            match Handle::try_current() {
+                Err(_) => self.runtime.block_on(future),
+                Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                    tokio::task::block_in_place(|| self.runtime.block_on(future))
+                }
+                // A current-thread runtime can't give up its only thread
+                Ok(_) => std::thread::scope(|scope| {
+                    scope
+                        .spawn(|| self.runtime.block_on(future))
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                }),
+            }
+        }
+
+        /// `None` for a prefix that names the backend root.
+        fn list_root(&self, prefix: &str) -> Option<ObjectPath> {
+            let location = self.location(prefix);
+            (!location.as_ref().is_empty()).then_some(location)
+        }
+    }
+
+    fn storage_error(e: object_store::Error) -> MarketDataError {
+        MarketDataError::Storage(e.to_string())
+    }
+
+    fn not_found_as_none<T>(result: object_store::Result<T>) -> Result<Option<T>, MarketDataError> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    impl StorageBackend for ObjectStoreBackend {
+        fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+            let location = self.location(key);
+            self.block_on(async {
+                match not_found_as_none(self.store.get(&location).await)? {
+                    Some(result) => result.bytes().await.map(Some).map_err(storage_error),
+                    None => Ok(None),
+                }
+            })
+        }
+
+        fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+            let location = self.location(key);
+            let meta = not_found_as_none(self.block_on(self.store.head(&location)))?;
+            Ok(meta.map(|meta| ObjectMeta {
+                size: meta.size,
+                last_modified: Some(meta.last_modified.into()),
+            }))
+        }
+
+        fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+            let location = self.location(key);
+            self.block_on(self.store.put(&location, PutPayload::from(data)))
+                .map_err(storage_error)?;
+            Ok(())
+        }
+
+        fn delete(&self, key: &str) -> Result<(), MarketDataError> {
+            let location = self.location(key);
+            not_found_as_none(self.block_on(self.store.delete(&location)))?;
+            Ok(())
+        }
+
+        fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            let root = self.list_root(prefix);
+            let objects: Vec<object_store::ObjectMeta> = self
+                .block_on(self.store.list(root.as_ref()).try_collect())
+                .map_err(storage_error)?;
+
+            let mut keys: Vec<String> = objects.iter().map(|o| self.key(&o.location)).collect();
+            keys.sort();
+            Ok(keys)
+        }
+
+        fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            let root = self.list_root(prefix);
+            let listing = self
+                .block_on(self.store.list_with_delimiter(root.as_ref()))
+                .map_err(storage_error)?;
+
+            let mut names: Vec<String> = listing
+                .common_prefixes
+                .iter()
+                .filter_map(|p| p.filename().map(str::to_string))
+                .collect();
+            names.sort();
+            Ok(names)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Behaviour every backend must share.
+    fn exercise(backend: &dyn StorageBackend) {
+        assert_eq!(
+            backend.get("AAPL/2025/01/2025-01-15.parquet").unwrap(),
+            None
+        );
+        assert_eq!(
+            backend.head("AAPL/2025/01/2025-01-15.parquet").unwrap(),
+            None
+        );
+        assert!(backend.list("").unwrap().is_empty());
+        assert!(backend.list_prefixes("").unwrap().is_empty());
+
+        for key in [
+            "AAPL/2025/01/2025-01-15.parquet",
+            "AAPL/2025/01/2025-01-14.parquet",
+            "AAPL/2024/12/2024-12-31.parquet",
+            "MSFT/2025/01/2025-01-15.parquet",
+        ] {
+            backend.put(key, Bytes::from(key.to_string())).unwrap();
+        }
+        backend
+            .put(
+                "AAPL/2025/01/2025-01-15.parquet",
+                Bytes::from_static(b"new"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            backend.get("AAPL/2025/01/2025-01-15.parquet").unwrap(),
+            Some(Bytes::from_static(b"new"))
+        );
+        assert_eq!(
+            backend
+                .head("AAPL/2025/01/2025-01-15.parquet")
+                .unwrap()
+                .unwrap()
+                .size,
+            3
+        );
+        assert_eq!(backend.list_prefixes("").unwrap(), vec!["AAPL", "MSFT"]);
+        assert_eq!(backend.list_prefixes("AAPL").unwrap(), vec!["2024", "2025"]);
+        assert_eq!(
+            backend.list("AAPL").unwrap(),
+            vec![
+                "AAPL/2024/12/2024-12-31.parquet",
+                "AAPL/2025/01/2025-01-14.parquet",
+                "AAPL/2025/01/2025-01-15.parquet",
+            ]
+        );
+        // A prefix matches whole path segments only
+        assert!(backend.list("AAP").unwrap().is_empty());
+
+        backend.delete("MSFT/2025/01/2025-01-15.parquet").unwrap();
+        backend.delete("MSFT/2025/01/2025-01-15.parquet").unwrap();
+        assert_eq!(backend.list("MSFT").unwrap(), Vec::<String>::new());
+        assert_eq!(backend.list("").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn local_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = LocalBackend::new(dir.path());
+        exercise(&backend);
+
+        // Keys map onto the on-disk layout, with no temp files left behind
+        let day_dir = dir.path().join("AAPL").join("2025").join("01");
+        assert!(day_dir.join("2025-01-15.parquet").is_file());
+        assert_eq!(std::fs::read_dir(&day_dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn memory_backend() {
+        exercise(&MemoryBackend::new());
+    }
+
+    #[cfg(feature = "object-store")]
+    #[test]
+    fn object_store_backend() {
+        let store = std::sync::Arc::new(object_store::memory::InMemory::new());
+        exercise(&ObjectStoreBackend::new(store.clone(), "").unwrap());
+
+        // A prefix keeps the store's keys inside a sub-tree of the bucket
+        let prefixed = ObjectStoreBackend::new(store, "datasets/candles").unwrap();
+        prefixed
+            .put("AAPL/2025/01/2025-01-15.parquet", Bytes::new())
+            .unwrap();
+        assert_eq!(
+            prefixed.list("").unwrap(),
+            vec!["AAPL/2025/01/2025-01-15.parquet"]
+        );
+        assert_eq!(prefixed.list_prefixes("").unwrap(), vec!["AAPL"]);
+    }
+
+    #[cfg(feature = "object-store")]
+    #[tokio::test]
+    async fn object_store_backend_inside_runtime() {
+        let store = std::sync::Arc::new(object_store::memory::InMemory::new());
+        let backend = ObjectStoreBackend::new(store, "").unwrap();
+        backend.put("AAPL/2025.parquet", Bytes::new()).unwrap();
+        assert_eq!(backend.list("").unwrap(), vec!["AAPL/2025.parquet"]);
+        // Dropping on an async thread must not shut down a runtime
+        drop(backend);
+    }
+
+    #[cfg(feature = "object-store")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn object_store_backend_inside_multi_thread_runtime() {
+        let store = std::sync::Arc::new(object_store::memory::InMemory::new());
+        let backend = ObjectStoreBackend::new(store, "").unwrap();
+        backend.put("AAPL/2025.parquet", Bytes::new()).unwrap();
+        assert!(backend.head("AAPL/2025.parquet").unwrap().is_some());
+        drop(backend);
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use chrono::NaiveDate;
+
+use crate::candle::{Candle, Timeframe};
+
+/// Hit/miss counters and occupancy of a [`CandleCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+    pub max_bytes: usize,
+}
+
+/// In-process LRU cache of decoded day files, bounded by approximate size in bytes.
+///
+/// Entries are keyed by symbol and date and remember the key and modification
+/// time of the file they were decoded from, so a rewritten or newly shadowed
+/// file is re-read instead of served stale.
+#[derive(Debug)]
+pub struct CandleCache {
+    max_bytes: usize,
+    state: Mutex<LruState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<(String, NaiveDate), Entry>,
+    /// Last-use tick -> key, oldest first.
+    recency: BTreeMap<u64, (String, NaiveDate)>,
+    bytes: usize,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    candles: Vec<Candle>,
+    timeframe: Timeframe,
+    source: String,
+    modified: Option<SystemTime>,
+    bytes: usize,
+    last_used: u64,
+}
+
+impl CandleCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            state: Mutex::new(LruState::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Key of the file a cached day was decoded from, without counting a
+    /// hit or miss.
+    pub fn source(&self, symbol: &str, date: NaiveDate) -> Option<String> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .entries
+            .get(&(symbol.to_string(), date))
+            .map(|entry| entry.source.clone())
+    }
+
+    /// Look up a day and its bar interval, counting a hit or miss. Entries
+    /// decoded from another file than `source`, or whose recorded
+    /// modification time differs from `modified`, are dropped.
+    pub fn get(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        source: &str,
+        modified: Option<SystemTime>,
+    ) -> Option<(Vec<Candle>, Timeframe)> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (symbol.to_string(), date);
+
+        let fresh = match state.entries.get(&key) {
+            Some(entry) => entry.source == source && entry.modified == modified,
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+        if !fresh {
+            state.remove(&key);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        let entry = state.entries.get_mut(&key)?;
+        let previous = std::mem::replace(&mut entry.last_used, tick);
+        let day = (entry.candles.clone(), entry.timeframe);
+        state.recency.remove(&previous);
+        state.recency.insert(tick, key);
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(day)
+    }
+
+    /// Insert a day decoded from the file at `source`, evicting least
+    /// recently used entries to stay within the byte bound. Days larger than
+    /// the whole cache are not stored.
+    pub fn insert(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        source: &str,
+        modified: Option<SystemTime>,
+        candles: Vec<Candle>,
+        timeframe: Timeframe,
+    ) {
+        let bytes = entry_size(&candles);
+        if bytes > self.max_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (symbol.to_string(), date);
+        state.remove(&key);
+
+        while state.bytes + bytes > self.max_bytes {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            if let Some(evicted) = state.entries.remove(&oldest) {
+                state.bytes -= evicted.bytes;
+            }
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        state.recency.insert(tick, key.clone());
+        state.bytes += bytes;
+        state.entries.insert(
+            key,
+            Entry {
+                candles,
+                timeframe,
+                source: source.to_string(),
+                modified,
+                bytes,
+                last_used: tick,
+            },
+        );
+    }
+
+    /// Drop the cached days of `symbol` from `start` to `end` inclusive.
+    pub fn invalidate(&self, symbol: &str, start: NaiveDate, end: NaiveDate) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let stale: Vec<(String, NaiveDate)> = state
+            .entries
+            .keys()
+            .filter(|(s, date)| s == symbol && start <= *date && *date <= end)
+            .cloned()
+            .collect();
+        for key in &stale {
+            state.remove(key);
+        }
+    }
+
+    /// Drop every cached day.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state = LruState::default();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: state.entries.len(),
+            bytes: state.bytes,
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
+impl LruState {
+    fn remove(&mut self, key: &(String, NaiveDate)) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+            self.bytes -= entry.bytes;
+        }
+    }
+}
+
+/// Approximate heap footprint of a cached day.
+fn entry_size(candles: &[Candle]) -> usize {
+    std::mem::size_of::<Entry>() + std::mem::size_of_val(candles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    const KEY: &str = "AAPL/2025/01.parquet";
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, d).unwrap()
+    }
+
+    fn candles(n: usize) -> Vec<Candle> {
+        (0..n)
+            .map(|i| Candle {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap()
+                    + chrono::Duration::minutes(5 * i as i64),
+                open: dec!(150.00),
+                high: dec!(151.00),
+                low: dec!(149.00),
+                close: dec!(150.50),
+                volume: 1000,
+                vwap: None,
+                trade_count: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hit_and_miss_counters() {
+        let cache = CandleCache::new(1 << 20);
+        assert!(cache.get("AAPL", date(15), KEY, None).is_none());
+        cache.insert(
+            "AAPL",
+            date(15),
+            KEY,
+            None,
+            candles(2),
+            Timeframe::FiveMinute,
+        );
+        assert_eq!(cache.get("AAPL", date(15), KEY, None).unwrap().0.len(), 2);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    }
+
+    #[test]
+    fn changed_mtime_is_a_miss() {
+        let cache = CandleCache::new(1 << 20);
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + std::time::Duration::from_secs(1);
+        cache.insert(
+            "AAPL",
+            date(15),
+            KEY,
+            Some(t0),
+            candles(2),
+            Timeframe::FiveMinute,
+        );
+
+        assert!(cache.get("AAPL", date(15), KEY, Some(t1)).is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn other_source_is_a_miss() {
+        let cache = CandleCache::new(1 << 20);
+        cache.insert(
+            "AAPL",
+            date(15),
+            KEY,
+            None,
+            candles(2),
+            Timeframe::FiveMinute,
+        );
+        assert_eq!(cache.source("AAPL", date(15)).as_deref(), Some(KEY));
+
+        assert!(
+            cache
+                .get("AAPL", date(15), "AAPL/2025/01/2025-01-15.parquet", None)
+                .is_none()
+        );
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn invalidate_drops_range() {
+        let cache = CandleCache::new(1 << 20);
+        for day in [14, 15, 16] {
+            cache.insert(
+                "AAPL",
+                date(day),
+                KEY,
+                None,
+                candles(1),
+                Timeframe::FiveMinute,
+            );
+        }
+        cache.insert(
+            "MSFT",
+            date(15),
+            KEY,
+            None,
+            candles(1),
+            Timeframe::FiveMinute,
+        );
+
+        cache.invalidate("AAPL", date(15), date(16));
+        assert!(cache.source("AAPL", date(14)).is_some());
+        assert!(cache.source("AAPL", date(15)).is_none());
+        assert!(cache.source("AAPL", date(16)).is_none());
+        assert!(cache.source("MSFT", date(15)).is_some());
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let size = entry_size(&candles(10));
+        let cache = CandleCache::new(size * 2);
+        cache.insert(
+            "AAPL",
+            date(13),
+            KEY,
+            None,
+            candles(10),
+            Timeframe::FiveMinute,
+        );
+        cache.insert(
+            "AAPL",
+            date(14),
+            KEY,
+            None,
+            candles(10),
+            Timeframe::FiveMinute,
+        );
+
+        // Touch the 13th so the 14th becomes the eviction candidate
+        cache.get("AAPL", date(13), KEY, None).unwrap();
+        cache.insert(
+            "AAPL",
+            date(15),
+            KEY,
+            None,
+            candles(10),
+            Timeframe::FiveMinute,
+        );
+
+        assert!(cache.get("AAPL", date(13), KEY, None).is_some());
+        assert!(cache.get("AAPL", date(14), KEY, None).is_none());
+        assert!(cache.get("AAPL", date(15), KEY, None).is_some());
+        assert!(cache.stats().bytes <= size * 2);
+    }
+
+    #[test]
+    fn oversized_entry_not_cached() {
+        let cache = CandleCache::new(16);
+        cache.insert(
+            "AAPL",
+            date(15),
+            KEY,
+            None,
+            candles(10),
+            Timeframe::FiveMinute,
+        );
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
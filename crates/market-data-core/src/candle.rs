@@ -1,8 +1,15 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::America::New_York;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-/// A single 5-minute OHLCV candle.
+use crate::session::{Session, to_exchange_time};
+
+/// A single OHLCV candle, labeled by the start of its bar.
+///
+/// Intraday bars start on the 5-minute grid in exchange time; daily bars
+/// start at the regular-session open (9:30 ET) of their trading date.
+/// See [`Timeframe::bar_start`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Candle {
     pub timestamp: DateTime<Utc>,
@@ -11,4 +18,138 @@ pub struct Candle {
     pub low: Decimal,
     pub close: Decimal,
     pub volume: i64,
+    /// Volume-weighted average price over the bar, when the provider reports it.
+    #[serde(default)]
+    pub vwap: Option<Decimal>,
+    /// Number of trades in the bar, when the provider reports it.
+    #[serde(default)]
+    pub trade_count: Option<i64>,
+}
+
+impl Candle {
+    /// End of this candle's bar (exclusive). See [`Timeframe::bar_end`].
+    pub fn end_time(&self, timeframe: Timeframe) -> DateTime<Utc> {
+        timeframe.bar_end(&self.timestamp)
+    }
+
+    /// Whether the timestamp is the start of a bar on the timeframe grid.
+    pub fn is_aligned(&self, timeframe: Timeframe) -> bool {
+        timeframe.bar_start(&self.timestamp) == self.timestamp
+    }
+}
+
+/// Bar interval of a candle series, which determines its storage layout.
+///
+/// Intraday series are stored one file per day; daily series are packed into
+/// one file per year (see [`CandleStore::write_daily`](crate::store::CandleStore::write_daily)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Timeframe {
+    #[default]
+    FiveMinute,
+    Daily,
+}
+
+impl Timeframe {
+    /// Start of the bar containing `timestamp`.
+    ///
+    /// Five-minute bars are aligned to the 5-minute grid in exchange time,
+    /// which matches the UTC grid because ET offsets are whole hours. Daily
+    /// bars start at the regular-session open of the exchange-local date.
+    pub fn bar_start(self, timestamp: &DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::FiveMinute => {
+                let micros = timestamp.timestamp_micros();
+                let step = Duration::minutes(5).num_microseconds().unwrap_or(1);
+                DateTime::from_timestamp_micros(micros - micros.rem_euclid(step))
+                    .unwrap_or(*timestamp)
+            }
+            Self::Daily => Self::session_open(to_exchange_time(timestamp).date_naive()),
+        }
+    }
+
+    /// End of the bar starting at `start` (exclusive): five minutes later, or
+    /// the regular-session close (16:00 ET) of a daily bar's trading date.
+    pub fn bar_end(self, start: &DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::FiveMinute => *start + Duration::minutes(5),
+            Self::Daily => exchange_time(
+                to_exchange_time(start).date_naive(),
+                Session::Regular.hours().1,
+            ),
+        }
+    }
+
+    /// Start of the daily bar for a trading date: 9:30 ET, in UTC.
+    pub fn session_open(date: NaiveDate) -> DateTime<Utc> {
+        exchange_time(date, Session::Regular.hours().0)
+    }
+}
+
+/// A local exchange time on `date` in UTC. Session boundaries never fall in
+/// a DST transition, so the earliest mapping is the only one.
+pub(crate) fn exchange_time(date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    New_York
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| date.and_time(time).and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn candle(timestamp: DateTime<Utc>) -> Candle {
+        Candle {
+            timestamp,
+            open: dec!(150.00),
+            high: dec!(151.00),
+            low: dec!(149.00),
+            close: dec!(150.50),
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        }
+    }
+
+    #[test]
+    fn five_minute_grid() {
+        let on_grid = Utc.with_ymd_and_hms(2025, 1, 15, 20, 55, 0).unwrap();
+        let off_grid = Utc.with_ymd_and_hms(2025, 1, 15, 20, 58, 41).unwrap();
+        assert!(candle(on_grid).is_aligned(Timeframe::FiveMinute));
+        assert!(!candle(off_grid).is_aligned(Timeframe::FiveMinute));
+        assert_eq!(Timeframe::FiveMinute.bar_start(&off_grid), on_grid);
+        assert_eq!(
+            candle(on_grid).end_time(Timeframe::FiveMinute),
+            Utc.with_ymd_and_hms(2025, 1, 15, 21, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn daily_bars_follow_the_exchange_open_across_dst() {
+        let winter = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        let summer = NaiveDate::from_ymd_opt(2025, 7, 15).unwrap();
+        assert_eq!(
+            Timeframe::session_open(winter),
+            Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap()
+        );
+        assert_eq!(
+            Timeframe::session_open(summer),
+            Utc.with_ymd_and_hms(2025, 7, 15, 13, 30, 0).unwrap()
+        );
+
+        // A synthetic 14:30 UTC stamp is 10:30 ET in summer: off the daily grid
+        let synthetic = candle(Utc.with_ymd_and_hms(2025, 7, 15, 14, 30, 0).unwrap());
+        assert!(!synthetic.is_aligned(Timeframe::Daily));
+        assert_eq!(
+            Timeframe::Daily.bar_start(&synthetic.timestamp),
+            Timeframe::session_open(summer)
+        );
+        assert_eq!(
+            candle(Timeframe::session_open(summer)).end_time(Timeframe::Daily),
+            Utc.with_ymd_and_hms(2025, 7, 15, 20, 0, 0).unwrap()
+        );
+    }
 }
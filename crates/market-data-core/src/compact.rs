@@ -0,0 +1,410 @@
+use std::collections::{BTreeMap, HashSet};
+
+use bytes::Bytes;
+use chrono::{Datelike, NaiveDate};
+
+use crate::candle::{Candle, Timeframe};
+use crate::error::MarketDataError;
+use crate::schema;
+use crate::store::{CandleStore, DataKey, parse_key};
+
+/// Span of history packed into one file by [`CandleStore::compact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackPeriod {
+    Month,
+    Year,
+}
+
+impl PackPeriod {
+    /// First day of the period containing `date`.
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Month => date.with_day(1),
+            Self::Year => date.with_ordinal(1),
+        }
+        .unwrap_or(date)
+    }
+
+    /// Last day of the period containing `date`.
+    pub fn end(self, date: NaiveDate) -> NaiveDate {
+        let next = match self {
+            Self::Month if date.month() == 12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+            Self::Month => NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1),
+            Self::Year => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        };
+        next.and_then(|d| d.pred_opt()).unwrap_or(date)
+    }
+
+    fn key(self, symbol: &str, date: NaiveDate) -> String {
+        match self {
+            Self::Month => CandleStore::month_key(symbol, date.year(), date.month()),
+            Self::Year => CandleStore::year_key(symbol, date.year()),
+        }
+    }
+}
+
+/// Files changed by [`CandleStore::compact`] or [`CandleStore::uncompact`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactReport {
+    /// Pack files written.
+    pub packs_written: usize,
+    /// Day files written (by `uncompact`) or folded into packs (by `compact`).
+    pub day_files: usize,
+    /// Pack files deleted: month packs merged into a year, or unpacked packs.
+    pub packs_removed: usize,
+}
+
+/// Sources merged into one pack, lowest precedence first.
+#[derive(Default)]
+struct PackSources {
+    month_packs: Vec<String>,
+    day_files: Vec<(NaiveDate, String)>,
+}
+
+impl CandleStore {
+    /// Pack a symbol's day files into one file per `period` that ends before
+    /// `before`, so closed history takes a handful of files.
+    ///
+    /// Packs list their dates in the footer and are read transparently. A
+    /// period's existing pack and, for years, its month packs are merged in,
+    /// with day files taking precedence. Sources are deleted only after the
+    /// pack is written, so an interrupted run leaves every day readable.
+    pub fn compact(
+        &self,
+        symbol: &str,
+        period: PackPeriod,
+        before: NaiveDate,
+    ) -> Result<CompactReport, MarketDataError> {
+        let mut groups: BTreeMap<NaiveDate, PackSources> = BTreeMap::new();
+        for key in self.backend().list(symbol)? {
+            let date = match parse_key(&key) {
+                Some(DataKey::Day(date)) => date,
+                Some(DataKey::MonthPack(year, month)) if period == PackPeriod::Year => {
+                    let Some(date) = NaiveDate::from_ymd_opt(year, month, 1) else {
+                        continue;
+                    };
+                    if period.end(date) < before {
+                        groups
+                            .entry(period.start(date))
+                            .or_default()
+                            .month_packs
+                            .push(key);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            if period.end(date) < before {
+                groups
+                    .entry(period.start(date))
+                    .or_default()
+                    .day_files
+                    .push((date, key));
+            }
+        }
+
+        let mut report = CompactReport::default();
+        for (start, sources) in groups {
+            let key = period.key(symbol, start);
+            let mut days: BTreeMap<NaiveDate, Vec<Candle>> = BTreeMap::new();
+            let mut timeframe = Timeframe::FiveMinute;
+            for pack in std::iter::once(&key).chain(&sources.month_packs) {
+                if let Some(data) = self.backend().get(pack)? {
+                    if pack == &key {
+                        // Keep a daily pack marked as daily
+                        timeframe =
+                            schema::decode_timeframe(data.clone()).map_err(|e| e.in_file(pack))?;
+                    }
+                    for candle in schema::decode_parquet(data).map_err(|e| e.in_file(pack))? {
+                        days.entry(candle.timestamp.date_naive())
+                            .or_default()
+                            .push(candle);
+                    }
+                }
+            }
+            for (date, _) in &sources.day_files {
+                days.insert(*date, self.read_day(symbol, *date)?);
+            }
+
+            let mut candles: Vec<Candle> = days.into_values().flatten().collect();
+            candles.sort_by_key(|c| c.timestamp);
+            let data = schema::encode_pack_with(&candles, self.config(), timeframe)?;
+            self.backend().put(&key, Bytes::from(data))?;
+            self.evict(&key);
+            report.packs_written += 1;
+
+            for (_, day_key) in &sources.day_files {
+                self.backend().delete(day_key)?;
+            }
+            for pack in &sources.month_packs {
+                self.backend().delete(pack)?;
+            }
+            report.day_files += sources.day_files.len();
+            report.packs_removed += sources.month_packs.len();
+        }
+        Ok(report)
+    }
+
+    /// Inverse of [`CandleStore::compact`]: split every pack of a symbol back
+    /// into day files and delete the packs. Days that already have a day file
+    /// keep it.
+    ///
+    /// Day files can only hold five-minute bars, so daily series stay packed:
+    /// per-year daily packs are kept, and daily month packs are merged into
+    /// them as in [`CandleStore::write_daily`].
+    pub fn uncompact(&self, symbol: &str) -> Result<CompactReport, MarketDataError> {
+        let mut day_files = HashSet::new();
+        let mut month_packs = Vec::new();
+        let mut year_packs = Vec::new();
+        for key in self.backend().list(symbol)? {
+            match parse_key(&key) {
+                Some(DataKey::Day(date)) => {
+                    day_files.insert(date);
+                }
+                Some(DataKey::MonthPack(..)) => month_packs.push(key),
+                Some(DataKey::YearPack(_)) => year_packs.push(key),
+                None => {}
+            }
+        }
+
+        // Month packs take precedence over year packs
+        let mut report = CompactReport::default();
+        let mut unpacked = Vec::new();
+        let mut daily_months = Vec::new();
+        let mut daily = Vec::new();
+        for pack in month_packs.iter().chain(&year_packs) {
+            let Some(data) = self.backend().get(pack)? else {
+                continue;
+            };
+            let timeframe = schema::decode_timeframe(data.clone()).map_err(|e| e.in_file(pack))?;
+            if timeframe == Timeframe::Daily && year_packs.contains(pack) {
+                continue;
+            }
+            let mut days: BTreeMap<NaiveDate, Vec<Candle>> = BTreeMap::new();
+            for candle in schema::decode_parquet(data).map_err(|e| e.in_file(pack))? {
+                days.entry(candle.timestamp.date_naive())
+                    .or_default()
+                    .push(candle);
+            }
+            for (date, candles) in days {
+                if !day_files.insert(date) {
+                    continue;
+                }
+                if timeframe == Timeframe::Daily {
+                    daily.extend(candles);
+                } else {
+                    self.write_day(symbol, date, &candles)?;
+                    report.day_files += 1;
+                }
+            }
+            if timeframe == Timeframe::Daily {
+                daily_months.push(pack);
+            } else {
+                unpacked.push(pack);
+            }
+        }
+
+        // Five-minute year packs go before daily bars are packed by year, so
+        // they aren't merged into the daily packs
+        for pack in unpacked {
+            self.backend().delete(pack)?;
+            self.evict(pack);
+            report.packs_removed += 1;
+        }
+        if !daily.is_empty() {
+            let years: HashSet<i32> = daily.iter().map(|c| c.timestamp.year()).collect();
+            self.write_daily(symbol, &daily)?;
+            report.packs_written += years.len();
+        }
+        for pack in daily_months {
+            self.backend().delete(pack)?;
+            self.evict(pack);
+            report.packs_removed += 1;
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MemoryBackend, StorageBackend};
+    use crate::session::Session;
+    use crate::trading_calendar;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn day(d: NaiveDate) -> Vec<Candle> {
+        (0..78)
+            .map(|i| Candle {
+                timestamp: Utc
+                    .with_ymd_and_hms(d.year(), d.month(), d.day(), 14, 30, 0)
+                    .unwrap()
+                    + chrono::Duration::minutes(5 * i),
+                open: dec!(150.00),
+                high: dec!(151.00),
+                low: dec!(149.00),
+                close: dec!(150.50),
+                volume: 1000 + i,
+                vwap: None,
+                trade_count: None,
+            })
+            .collect()
+    }
+
+    fn populated() -> (Arc<MemoryBackend>, CandleStore, Vec<NaiveDate>) {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone());
+        let dates = trading_calendar::weekdays(date(2024, 11, 1), date(2025, 1, 31));
+        for &d in &dates {
+            store.write_day("AAPL", d, &day(d)).unwrap();
+        }
+        (backend, store, dates)
+    }
+
+    #[test]
+    fn period_bounds() {
+        let d = date(2024, 12, 15);
+        assert_eq!(PackPeriod::Month.start(d), date(2024, 12, 1));
+        assert_eq!(PackPeriod::Month.end(d), date(2024, 12, 31));
+        assert_eq!(PackPeriod::Month.end(date(2024, 2, 3)), date(2024, 2, 29));
+        assert_eq!(PackPeriod::Year.start(d), date(2024, 1, 1));
+        assert_eq!(PackPeriod::Year.end(d), date(2024, 12, 31));
+    }
+
+    #[test]
+    fn compact_months_then_years_is_transparent() {
+        let (backend, store, dates) = populated();
+        let before = store
+            .read_range("AAPL", dates[0], date(2025, 1, 31))
+            .unwrap();
+
+        // January 2025 is still open on the 20th
+        let report = store
+            .compact("AAPL", PackPeriod::Month, date(2025, 1, 20))
+            .unwrap();
+        assert_eq!(report.packs_written, 2);
+        assert_eq!(report.day_files, 21 + 22);
+        assert!(
+            backend
+                .head(&CandleStore::month_key("AAPL", 2024, 11))
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(backend.list("AAPL").unwrap().len(), 2 + 23);
+
+        assert_eq!(store.list_dates("AAPL").unwrap(), dates);
+        assert!(
+            store
+                .missing_dates("AAPL", dates[0], date(2025, 1, 31))
+                .is_empty()
+        );
+        assert_eq!(
+            store
+                .read_range("AAPL", dates[0], date(2025, 1, 31))
+                .unwrap(),
+            before
+        );
+        let d = date(2024, 12, 3);
+        assert_eq!(store.read_day("AAPL", d).unwrap(), day(d));
+        assert_eq!(store.day_stats("AAPL", d).unwrap().row_count, 78);
+        assert_eq!(
+            store
+                .read_range_session("AAPL", d, d, Session::Regular)
+                .unwrap()
+                .len(),
+            78
+        );
+
+        // A corrected day file shadows its packed copy until the next compaction
+        let mut corrected = day(d);
+        corrected.truncate(10);
+        store.write_day("AAPL", d, &corrected).unwrap();
+        assert_eq!(store.read_day("AAPL", d).unwrap().len(), 10);
+
+        // Year compaction folds the month packs and the correction in
+        let report = store
+            .compact("AAPL", PackPeriod::Year, date(2025, 1, 20))
+            .unwrap();
+        assert_eq!(report.packs_written, 1);
+        assert_eq!(report.day_files, 1);
+        assert_eq!(report.packs_removed, 2);
+        assert_eq!(backend.list("AAPL").unwrap().len(), 1 + 23);
+        assert_eq!(store.read_day("AAPL", d).unwrap(), corrected);
+        assert_eq!(store.list_dates("AAPL").unwrap(), dates);
+    }
+
+    #[test]
+    fn uncompact_restores_day_files() {
+        let (backend, store, dates) = populated();
+        let original = backend.list("AAPL").unwrap();
+        store
+            .compact("AAPL", PackPeriod::Year, date(2026, 1, 1))
+            .unwrap();
+        assert_eq!(backend.list("AAPL").unwrap().len(), 2);
+
+        let report = store.uncompact("AAPL").unwrap();
+        assert_eq!(report.day_files, dates.len());
+        assert_eq!(report.packs_removed, 2);
+        assert_eq!(backend.list("AAPL").unwrap(), original);
+        let d = date(2025, 1, 15);
+        assert_eq!(store.read_day("AAPL", d).unwrap(), day(d));
+    }
+
+    #[test]
+    fn uncompact_keeps_daily_series_daily() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone());
+        let bar = |d: NaiveDate, close| Candle {
+            timestamp: Timeframe::session_open(d),
+            open: dec!(18.00),
+            high: dec!(19.00),
+            low: dec!(17.00),
+            close,
+            volume: 0,
+            vwap: None,
+            trade_count: None,
+        };
+        store
+            .write_daily(
+                "VIX",
+                &[
+                    bar(date(2024, 12, 30), dec!(17.35)),
+                    bar(date(2025, 1, 2), dec!(17.93)),
+                ],
+            )
+            .unwrap();
+        // A daily month pack, as left by an older layout
+        let january = [bar(date(2025, 1, 3), dec!(16.13))];
+        let data = schema::encode_pack_with(&january, store.config(), Timeframe::Daily).unwrap();
+        backend
+            .put(&CandleStore::month_key("VIX", 2025, 1), Bytes::from(data))
+            .unwrap();
+
+        let report = store.uncompact("VIX").unwrap();
+        assert_eq!(report.day_files, 0);
+        assert_eq!(report.packs_written, 1);
+        assert_eq!(report.packs_removed, 1);
+        assert_eq!(
+            backend.list("VIX").unwrap(),
+            vec![
+                CandleStore::year_key("VIX", 2024),
+                CandleStore::year_key("VIX", 2025)
+            ]
+        );
+        for (d, close) in [
+            (date(2024, 12, 30), dec!(17.35)),
+            (date(2025, 1, 2), dec!(17.93)),
+            (date(2025, 1, 3), dec!(16.13)),
+        ] {
+            let (candles, timeframe) = store.read_day_bars("VIX", d).unwrap();
+            assert_eq!(timeframe, Timeframe::Daily);
+            assert_eq!(candles, vec![bar(d, close)]);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet::file::properties::EnabledStatistics;
+use serde::{Deserialize, Serialize};
+
+use crate::error::MarketDataError;
+use crate::precision::PriceRules;
+
+/// Backend key of the persisted [`StoreConfig`], at the data root.
+pub const CONFIG_FILE: &str = "store.json";
+
+/// Five-minute bars in a regular 6.5 hour session, the default page size.
+const SESSION_BARS: usize = 78;
+
+/// Parquet compression codec for day files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "codec", rename_all = "lowercase")]
+pub enum Codec {
+    Uncompressed,
+    #[default]
+    Snappy,
+    Lz4,
+    /// Levels 1-22; higher is smaller and slower.
+    Zstd {
+        level: i32,
+    },
+    /// Levels 0-9.
+    Gzip {
+        level: u32,
+    },
+}
+
+impl Codec {
+    fn to_parquet(self) -> Result<Compression, MarketDataError> {
+        let invalid = |e: parquet::errors::ParquetError| {
+            MarketDataError::InvalidData(format!("invalid compression level: {e}"))
+        };
+        Ok(match self {
+            Self::Uncompressed => Compression::UNCOMPRESSED,
+            Self::Snappy => Compression::SNAPPY,
+            Self::Lz4 => Compression::LZ4_RAW,
+            Self::Zstd { level } => Compression::ZSTD(ZstdLevel::try_new(level).map_err(invalid)?),
+            Self::Gzip { level } => Compression::GZIP(GzipLevel::try_new(level).map_err(invalid)?),
+        })
+    }
+}
+
+/// How much column statistics to write for price and volume columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatisticsLevel {
+    None,
+    Chunk,
+    #[default]
+    Page,
+}
+
+impl From<StatisticsLevel> for EnabledStatistics {
+    fn from(level: StatisticsLevel) -> Self {
+        match level {
+            StatisticsLevel::None => EnabledStatistics::None,
+            StatisticsLevel::Chunk => EnabledStatistics::Chunk,
+            StatisticsLevel::Page => EnabledStatistics::Page,
+        }
+    }
+}
+
+/// Writer settings for a store's Parquet files.
+///
+/// Set with [`CandleStore::with_config`](crate::store::CandleStore::with_config)
+/// and persisted beside the data as [`CONFIG_FILE`], so later writes and
+/// rewrites use the same settings. The timestamp column always keeps page
+/// statistics, which time-window reads and footer stats depend on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoreConfig {
+    pub compression: Codec,
+    /// Dictionary-encode columns. Helps repetitive prices compress.
+    pub dictionary: bool,
+    /// Statistics for every column except `timestamp`.
+    pub statistics: StatisticsLevel,
+    /// Maximum rows per row group.
+    pub max_row_group_size: usize,
+    /// Maximum rows per data page in day files. Smaller pages let time-window
+    /// reads skip more of a file but cost space in per-page headers and
+    /// statistics. Pack files ignore this and use Parquet's byte-sized pages.
+    pub page_rows: usize,
+    /// Write bloom filters with this false-positive probability, if set.
+    pub bloom_filter_fpp: Option<f64>,
+    /// Round prices with these rules on every write and rewrite, if set.
+    pub prices: Option<PriceRules>,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        Self {
+            compression: Codec::Snappy,
+            dictionary: true,
+            statistics: StatisticsLevel::Page,
+            max_row_group_size: 1024 * 1024,
+            page_rows: SESSION_BARS,
+            bloom_filter_fpp: None,
+            prices: None,
+        }
+    }
+}
+
+impl StoreConfig {
+    /// Settings tuned for long-term archival: ZSTD at `level`.
+    pub fn archival(level: i32) -> Self {
+        Self {
+            compression: Codec::Zstd { level },
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn compression(&self) -> Result<Compression, MarketDataError> {
+        self.compression.to_parquet()
+    }
+
+    /// Check that the settings can be used to write files.
+    pub fn validate(&self) -> Result<(), MarketDataError> {
+        self.compression()?;
+        if self.max_row_group_size == 0 {
+            return Err(MarketDataError::InvalidData(
+                "max_row_group_size must be positive".into(),
+            ));
+        }
+        if self.page_rows == 0 {
+            return Err(MarketDataError::InvalidData(
+                "page_rows must be positive".into(),
+            ));
+        }
+        if let Some(fpp) = self.bloom_filter_fpp
+            && !(fpp > 0.0 && fpp < 1.0)
+        {
+            return Err(MarketDataError::InvalidData(format!(
+                "bloom_filter_fpp must be between 0 and 1, got {fpp}"
+            )));
+        }
+        if let Some(prices) = &self.prices {
+            prices.validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, MarketDataError> {
+        serde_json::to_vec_pretty(self)
+            .map_err(|e| MarketDataError::InvalidData(format!("failed to encode config: {e}")))
+    }
+
+    pub fn from_json(data: &[u8]) -> Result<Self, MarketDataError> {
+        let config: Self = serde_json::from_slice(data)
+            .map_err(|e| MarketDataError::InvalidData(format!("invalid {CONFIG_FILE}: {e}")))?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precision::PriceRule;
+
+    #[test]
+    fn json_roundtrip_and_defaults() {
+        let config = StoreConfig {
+            bloom_filter_fpp: Some(0.01),
+            page_rows: 12,
+            prices: Some(PriceRules::default().with_symbol("VIX", PriceRule::scale(2))),
+            ..StoreConfig::archival(9)
+        };
+        assert_eq!(
+            StoreConfig::from_json(&config.to_json().unwrap()).unwrap(),
+            config
+        );
+
+        // Missing fields fall back to defaults
+        let partial =
+            StoreConfig::from_json(br#"{"compression": {"codec": "zstd", "level": 3}}"#).unwrap();
+        assert_eq!(partial, StoreConfig::archival(3));
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(StoreConfig::archival(99).validate().is_err());
+        assert!(
+            StoreConfig {
+                bloom_filter_fpp: Some(1.5),
+                ..StoreConfig::default()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(StoreConfig::from_json(br#"{"page_rows": 0}"#).is_err());
+        assert!(StoreConfig::from_json(br#"{"compression": {"codec": "brotli"}}"#).is_err());
+        assert!(StoreConfig::from_json(br#"{"prices": {"default": {"scale": 40}}}"#).is_err());
+    }
+}
@@ -17,6 +17,33 @@ pub enum MarketDataError {
         date: chrono::NaiveDate,
     },
 
+    #[error("Conflicting candles for {symbol} at {timestamp}")]
+    ConflictingDuplicate {
+        symbol: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    #[error("Candle for {symbol} at {timestamp} is outside {date}")]
+    OutsideDate {
+        symbol: String,
+        date: chrono::NaiveDate,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
     #[error("Invalid data: {0}")]
     InvalidData(String),
 }
+
+impl MarketDataError {
+    /// Prefix an [`InvalidData`](Self::InvalidData) message with the file it
+    /// was read from. Other variants are returned unchanged.
+    pub fn in_file(self, file: impl std::fmt::Display) -> Self {
+        match self {
+            Self::InvalidData(message) => Self::InvalidData(format!("{file}: {message}")),
+            other => other,
+        }
+    }
+}
@@ -0,0 +1,619 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+
+use crate::candle::{Candle, Timeframe, exchange_time};
+use crate::error::MarketDataError;
+use crate::session::{Session, to_exchange_time};
+use crate::store::CandleStore;
+
+/// End of after-hours trading (ET) on early-close days.
+const EARLY_CLOSE_AFTER_HOURS_END: NaiveTime = NaiveTime::from_hms_opt(17, 0, 0).unwrap();
+
+/// The sessions a store is expected to cover on each trading day.
+///
+/// Trading days are weekdays that are not holidays. On an early-close day the
+/// regular session ends at the given time and after-hours trading runs from
+/// then until 17:00 ET, as on US exchanges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketSchedule {
+    sessions: Vec<Session>,
+    holidays: BTreeSet<NaiveDate>,
+    early_closes: BTreeMap<NaiveDate, NaiveTime>,
+}
+
+impl Default for MarketSchedule {
+    /// The regular session only, with no holidays.
+    fn default() -> Self {
+        Self::new(&[Session::Regular])
+    }
+}
+
+impl MarketSchedule {
+    /// Expect these sessions on every weekday.
+    pub fn new(sessions: &[Session]) -> Self {
+        Self {
+            sessions: Session::ALL
+                .into_iter()
+                .filter(|s| sessions.contains(s))
+                .collect(),
+            holidays: BTreeSet::new(),
+            early_closes: BTreeMap::new(),
+        }
+    }
+
+    /// Expect pre-market, regular and after-hours bars on every weekday.
+    pub fn extended() -> Self {
+        Self::new(&Session::ALL)
+    }
+
+    /// Mark a date as a market holiday, with no bars expected.
+    pub fn with_holiday(mut self, date: NaiveDate) -> Self {
+        self.holidays.insert(date);
+        self
+    }
+
+    /// Close the regular session early on `date`, at `close` in ET.
+    pub fn with_early_close(mut self, date: NaiveDate, close: NaiveTime) -> Self {
+        self.early_closes.insert(date, close);
+        self
+    }
+
+    /// Add holidays and early closes from a calendar file: one date per line,
+    /// `YYYY-MM-DD` for a holiday or `YYYY-MM-DD HH:MM` for an early close in
+    /// ET. Blank lines and `#` comments are ignored.
+    pub fn with_calendar(mut self, text: &str) -> Result<Self, MarketDataError> {
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |message: String| {
+                MarketDataError::InvalidData(format!("calendar line {}: {message}", index + 1))
+            };
+
+            let mut fields = line.split_whitespace();
+            let date = fields.next().unwrap_or_default();
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|e| invalid(format!("invalid date '{date}': {e}")))?;
+            match (fields.next(), fields.next()) {
+                (None, _) => self = self.with_holiday(date),
+                (Some(close), None) => {
+                    let close = NaiveTime::parse_from_str(close, "%H:%M")
+                        .map_err(|e| invalid(format!("invalid close '{close}': {e}")))?;
+                    let (open, regular_close) = Session::Regular.hours();
+                    if close <= open || close >= regular_close {
+                        return Err(invalid(format!(
+                            "early close {close} is not within the regular session"
+                        )));
+                    }
+                    self = self.with_early_close(date, close);
+                }
+                (Some(_), Some(_)) => {
+                    return Err(invalid(format!(
+                        "expected a date and optional close, got '{line}'"
+                    )));
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Expected sessions, in chronological order.
+    pub fn sessions(&self) -> &[Session] {
+        &self.sessions
+    }
+
+    /// Whether the market is open on `date`.
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    /// Trading days in the inclusive range [start, end].
+    pub fn trading_days(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        crate::trading_calendar::weekdays(start, end)
+            .into_iter()
+            .filter(|d| !self.holidays.contains(d))
+            .collect()
+    }
+
+    /// Exchange-local (ET) start and end of `session` on `date`, or `None`
+    /// if the session is not expected that day.
+    pub fn hours(&self, date: NaiveDate, session: Session) -> Option<(NaiveTime, NaiveTime)> {
+        if !self.is_trading_day(date) || !self.sessions.contains(&session) {
+            return None;
+        }
+        let (start, end) = session.hours();
+        Some(match (session, self.early_closes.get(&date)) {
+            (Session::Regular, Some(&close)) => (start, close),
+            (Session::AfterHours, Some(&close)) => (close, EARLY_CLOSE_AFTER_HOURS_END),
+            _ => (start, end),
+        })
+    }
+
+    /// Start of every bar expected in `session` on `date`. A daily bar is
+    /// expected at the regular-session open, and nowhere else.
+    pub fn slots(
+        &self,
+        date: NaiveDate,
+        session: Session,
+        timeframe: Timeframe,
+    ) -> Vec<DateTime<Utc>> {
+        let Some((start, end)) = self.hours(date, session) else {
+            return Vec::new();
+        };
+        match timeframe {
+            Timeframe::Daily if session == Session::Regular => vec![Timeframe::session_open(date)],
+            Timeframe::Daily => Vec::new(),
+            Timeframe::FiveMinute => {
+                let end = exchange_time(date, end);
+                let mut slot = exchange_time(date, start);
+                let mut slots = Vec::new();
+                while slot < end {
+                    slots.push(slot);
+                    slot += Duration::minutes(5);
+                }
+                slots
+            }
+        }
+    }
+}
+
+/// Why a run of bars is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GapKind {
+    /// A short run inside a session that has other bars: most likely nothing
+    /// traded, as is common for thin symbols on IEX.
+    NoTrades,
+    /// A long run, or a session with no bars at all: most likely a feed
+    /// outage or an incomplete download.
+    Outage,
+}
+
+impl fmt::Display for GapKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoTrades => "no trades",
+            Self::Outage => "outage",
+        })
+    }
+}
+
+/// A run of consecutive expected bars with no candle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gap {
+    /// Start of the first missing bar.
+    pub start: DateTime<Utc>,
+    /// End of the last missing bar (exclusive).
+    pub end: DateTime<Utc>,
+    /// Number of missing bars.
+    pub bars: usize,
+    pub kind: GapKind,
+}
+
+impl fmt::Display for Gap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}-{} ET, {} bar(s)",
+            self.kind,
+            to_exchange_time(&self.start).format("%H:%M"),
+            to_exchange_time(&self.end).format("%H:%M"),
+            self.bars
+        )
+    }
+}
+
+/// Coverage of one session on one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionCoverage {
+    pub session: Session,
+    /// Bars the schedule expects.
+    pub expected: usize,
+    /// Expected bars that have a candle.
+    pub present: usize,
+    /// Missing bars, as runs in time order.
+    pub gaps: Vec<Gap>,
+}
+
+impl SessionCoverage {
+    pub fn missing(&self) -> usize {
+        self.expected - self.present
+    }
+
+    /// Percentage of expected bars present.
+    pub fn completeness(&self) -> f64 {
+        percent(self.present, self.expected)
+    }
+}
+
+/// Coverage of every expected session on one trading day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayCoverage {
+    /// Exchange-local trading date.
+    pub date: NaiveDate,
+    pub sessions: Vec<SessionCoverage>,
+}
+
+impl DayCoverage {
+    pub fn expected(&self) -> usize {
+        self.sessions.iter().map(|s| s.expected).sum()
+    }
+
+    pub fn present(&self) -> usize {
+        self.sessions.iter().map(|s| s.present).sum()
+    }
+
+    /// Percentage of expected bars present across all sessions.
+    pub fn completeness(&self) -> f64 {
+        percent(self.present(), self.expected())
+    }
+
+    /// True if every expected bar is present.
+    pub fn is_complete(&self) -> bool {
+        self.present() == self.expected()
+    }
+
+    /// True if any gap is an [`GapKind::Outage`].
+    pub fn has_outage(&self) -> bool {
+        self.sessions
+            .iter()
+            .flat_map(|s| &s.gaps)
+            .any(|g| g.kind == GapKind::Outage)
+    }
+}
+
+/// Result of a gap analysis. See [`GapAnalyzer::analyze`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GapReport {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+    /// One entry per trading day in the range, by date.
+    pub days: Vec<DayCoverage>,
+}
+
+impl GapReport {
+    /// Percentage of expected bars present over the whole range.
+    pub fn completeness(&self) -> f64 {
+        percent(
+            self.days.iter().map(DayCoverage::present).sum(),
+            self.days.iter().map(DayCoverage::expected).sum(),
+        )
+    }
+
+    /// Days with at least one missing bar.
+    pub fn incomplete_days(&self) -> impl Iterator<Item = &DayCoverage> {
+        self.days.iter().filter(|d| !d.is_complete())
+    }
+
+    /// Number of days with an outage.
+    pub fn outage_days(&self) -> usize {
+        self.days.iter().filter(|d| d.has_outage()).count()
+    }
+}
+
+/// Compares stored bars with the bars a [`MarketSchedule`] expects.
+///
+/// Runs of up to [`GapAnalyzer::with_max_quiet_run`] missing bars inside a
+/// session that has other bars are [`GapKind::NoTrades`]; anything longer is
+/// an [`GapKind::Outage`]. A missing daily bar is always an outage.
+#[derive(Debug, Clone)]
+pub struct GapAnalyzer {
+    schedule: MarketSchedule,
+    timeframe: Timeframe,
+    max_quiet_run: usize,
+}
+
+impl GapAnalyzer {
+    /// Analyze `timeframe` bars against `schedule`, treating runs of up to
+    /// three missing bars as quiet periods.
+    pub fn new(schedule: MarketSchedule, timeframe: Timeframe) -> Self {
+        Self {
+            schedule,
+            timeframe,
+            max_quiet_run: 3,
+        }
+    }
+
+    /// Longest run of missing bars still attributed to no trades.
+    pub fn with_max_quiet_run(mut self, bars: usize) -> Self {
+        self.max_quiet_run = bars;
+        self
+    }
+
+    pub fn schedule(&self) -> &MarketSchedule {
+        &self.schedule
+    }
+
+    /// Check one trading day. `candles` may include bars of other days and
+    /// off-grid bars, which are ignored.
+    pub fn analyze_day(&self, date: NaiveDate, candles: &[Candle]) -> DayCoverage {
+        let stamps: BTreeSet<DateTime<Utc>> = candles.iter().map(|c| c.timestamp).collect();
+        let quiet_limit = match self.timeframe {
+            Timeframe::FiveMinute => self.max_quiet_run,
+            Timeframe::Daily => 0,
+        };
+
+        let sessions = self
+            .schedule
+            .sessions()
+            .iter()
+            .map(|&session| {
+                let slots = self.schedule.slots(date, session, self.timeframe);
+                let present = slots.iter().filter(|s| stamps.contains(s)).count();
+                let gaps = slots
+                    .split(|slot| stamps.contains(slot))
+                    .filter(|run| !run.is_empty())
+                    .map(|run| Gap {
+                        start: run[0],
+                        end: self.timeframe.bar_end(&run[run.len() - 1]),
+                        bars: run.len(),
+                        kind: if present > 0 && run.len() <= quiet_limit {
+                            GapKind::NoTrades
+                        } else {
+                            GapKind::Outage
+                        },
+                    })
+                    .collect();
+                SessionCoverage {
+                    session,
+                    expected: slots.len(),
+                    present,
+                    gaps,
+                }
+            })
+            .collect();
+        DayCoverage { date, sessions }
+    }
+
+    /// Check every trading day of `symbol` in the inclusive range
+    /// [start, end] of exchange-local dates. Days without a file count as
+    /// entirely missing.
+    pub fn analyze(
+        &self,
+        store: &CandleStore,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<GapReport, MarketDataError> {
+        // Evening ET bars land on the following UTC date, so read one extra day.
+        let read_end = end.succ_opt().unwrap_or(end);
+        let mut by_date: BTreeMap<NaiveDate, Vec<Candle>> = BTreeMap::new();
+        for candle in store.query(symbol).between(start, read_end).collect()? {
+            by_date
+                .entry(to_exchange_time(&candle.timestamp).date_naive())
+                .or_default()
+                .push(candle);
+        }
+
+        let days = self
+            .schedule
+            .trading_days(start, end)
+            .into_iter()
+            .map(|date| self.analyze_day(date, by_date.get(&date).map_or(&[], Vec::as_slice)))
+            .collect();
+        Ok(GapReport {
+            symbol: symbol.to_string(),
+            timeframe: self.timeframe,
+            days,
+        })
+    }
+}
+
+fn percent(present: usize, expected: usize) -> f64 {
+    if expected == 0 {
+        100.0
+    } else {
+        present as f64 * 100.0 / expected as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, m, d).unwrap()
+    }
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn candle(timestamp: DateTime<Utc>) -> Candle {
+        Candle {
+            timestamp,
+            open: dec!(150.00),
+            high: dec!(151.00),
+            low: dec!(149.00),
+            close: dec!(150.50),
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        }
+    }
+
+    /// Candles for every regular-session slot of a January day, but those in `skip`.
+    fn regular_day(d: u32, skip: impl Fn(usize) -> bool) -> Vec<Candle> {
+        MarketSchedule::default()
+            .slots(date(1, d), Session::Regular, Timeframe::FiveMinute)
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !skip(*i))
+            .map(|(_, slot)| candle(slot))
+            .collect()
+    }
+
+    #[test]
+    fn schedule_holidays_and_early_closes() {
+        let schedule = MarketSchedule::extended()
+            .with_calendar(
+                "# 2025 closures\n2025-07-04\n\n2025-07-03 13:00  # Independence Day eve\n",
+            )
+            .unwrap();
+        assert!(!schedule.is_trading_day(date(7, 4)));
+        assert!(!schedule.is_trading_day(date(7, 5)));
+        assert_eq!(
+            schedule.trading_days(date(7, 1), date(7, 7)),
+            vec![date(7, 1), date(7, 2), date(7, 3), date(7, 7)]
+        );
+
+        let count = |d, session| schedule.slots(d, session, Timeframe::FiveMinute).len();
+        assert_eq!(count(date(7, 2), Session::PreMarket), 66);
+        assert_eq!(count(date(7, 2), Session::Regular), 78);
+        assert_eq!(count(date(7, 2), Session::AfterHours), 48);
+        assert_eq!(count(date(7, 3), Session::Regular), 42);
+        assert_eq!(
+            schedule.hours(date(7, 3), Session::AfterHours),
+            Some((hm(13, 0), hm(17, 0)))
+        );
+        assert_eq!(count(date(7, 4), Session::Regular), 0);
+        assert_eq!(
+            schedule.slots(date(7, 2), Session::Regular, Timeframe::Daily),
+            vec![Timeframe::session_open(date(7, 2))]
+        );
+        assert!(
+            schedule
+                .slots(date(7, 2), Session::PreMarket, Timeframe::Daily)
+                .is_empty()
+        );
+
+        // Regular hours only by default
+        assert_eq!(MarketSchedule::default().sessions(), &[Session::Regular]);
+        assert_eq!(
+            MarketSchedule::default().hours(date(7, 2), Session::PreMarket),
+            None
+        );
+
+        for bad in [
+            "2025-13-01",
+            "2025-07-03 1pm",
+            "2025-07-03 17:00",
+            "2025-07-03 13:00 x",
+        ] {
+            assert!(
+                MarketSchedule::default().with_calendar(bad).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn flags_truncated_days_and_quiet_slots() {
+        let analyzer = GapAnalyzer::new(MarketSchedule::default(), Timeframe::FiveMinute);
+
+        // 40 of 78 regular bars: the feed stopped at 12:50 ET
+        let truncated = analyzer.analyze_day(date(1, 15), &regular_day(15, |i| i >= 40));
+        assert_eq!((truncated.expected(), truncated.present()), (78, 40));
+        assert!((truncated.completeness() - 51.28).abs() < 0.01);
+        assert!(truncated.has_outage());
+        let gaps = &truncated.sessions[0].gaps;
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].bars, 38);
+        assert_eq!(gaps[0].to_string(), "outage 12:50-16:00 ET, 38 bar(s)");
+
+        // Short runs with bars around them are quiet periods
+        let sparse =
+            analyzer.analyze_day(date(1, 15), &regular_day(15, |i| matches!(i, 5 | 20..23)));
+        assert_eq!(sparse.sessions[0].missing(), 4);
+        assert!(!sparse.has_outage());
+        assert_eq!(
+            sparse.sessions[0]
+                .gaps
+                .iter()
+                .map(|g| (g.bars, g.kind))
+                .collect::<Vec<_>>(),
+            vec![(1, GapKind::NoTrades), (3, GapKind::NoTrades)]
+        );
+        assert!(
+            analyzer
+                .clone()
+                .with_max_quiet_run(2)
+                .analyze_day(date(1, 15), &regular_day(15, |i| matches!(i, 20..23)))
+                .has_outage()
+        );
+
+        // A session with no bars is an outage however short
+        let empty = GapAnalyzer::new(MarketSchedule::default(), Timeframe::FiveMinute)
+            .with_max_quiet_run(100)
+            .analyze_day(date(1, 15), &[]);
+        assert_eq!(empty.completeness(), 0.0);
+        assert!(empty.has_outage());
+
+        // A missing daily bar is always an outage
+        let daily = GapAnalyzer::new(MarketSchedule::default(), Timeframe::Daily);
+        assert!(
+            daily
+                .analyze_day(date(1, 15), &[candle(Timeframe::session_open(date(1, 15)))])
+                .is_complete()
+        );
+        assert!(daily.analyze_day(date(1, 16), &[]).has_outage());
+    }
+
+    #[test]
+    fn analyzes_store_by_exchange_date() {
+        let store = CandleStore::with_backend(Arc::new(MemoryBackend::new()));
+        // After-hours runs to 01:00 UTC the next day, which for Friday Jan 17
+        // is a Saturday file
+        for day in [15, 17] {
+            let mut bars: Vec<Candle> = Session::ALL
+                .iter()
+                .flat_map(|&s| {
+                    MarketSchedule::extended().slots(date(1, day), s, Timeframe::FiveMinute)
+                })
+                .map(candle)
+                .collect();
+            let next_utc_day = bars.split_off(
+                bars.iter()
+                    .position(|c| c.timestamp.day() == day + 1)
+                    .unwrap(),
+            );
+            assert_eq!(
+                next_utc_day[0].timestamp,
+                Utc.with_ymd_and_hms(2025, 1, day + 1, 0, 0, 0).unwrap()
+            );
+            store.write_day("AAPL", date(1, day), &bars).unwrap();
+            store
+                .write_day("AAPL", date(1, day + 1), &next_utc_day)
+                .unwrap();
+        }
+        store
+            .write_day("AAPL", date(1, 21), &regular_day(21, |i| i >= 40))
+            .unwrap();
+
+        let schedule = MarketSchedule::extended().with_holiday(date(1, 20));
+        let report = GapAnalyzer::new(schedule, Timeframe::FiveMinute)
+            .analyze(&store, "AAPL", date(1, 15), date(1, 21))
+            .unwrap();
+        let dates: Vec<NaiveDate> = report.days.iter().map(|d| d.date).collect();
+        assert_eq!(
+            dates,
+            vec![date(1, 15), date(1, 16), date(1, 17), date(1, 21)]
+        );
+        assert!(report.days[0].is_complete());
+        assert_eq!(report.days[1].present(), 0);
+        assert!(report.days[2].is_complete());
+        assert_eq!(report.incomplete_days().count(), 2);
+        assert_eq!(report.outage_days(), 2);
+
+        let tuesday = &report.days[3];
+        assert_eq!(
+            tuesday
+                .sessions
+                .iter()
+                .map(|s| (s.session, s.present, s.expected))
+                .collect::<Vec<_>>(),
+            vec![
+                (Session::PreMarket, 0, 66),
+                (Session::Regular, 40, 78),
+                (Session::AfterHours, 0, 48),
+            ]
+        );
+        assert!((report.completeness() - 424.0 / 768.0 * 100.0).abs() < 1e-9);
+    }
+}
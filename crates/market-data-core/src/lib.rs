@@ -1,6 +1,20 @@
+#[cfg(feature = "async")]
+pub mod async_store;
+pub mod backend;
+pub mod cache;
 pub mod candle;
+pub mod compact;
+pub mod config;
 pub mod error;
+pub mod gaps;
+pub mod overlay;
+pub mod precision;
+pub mod query;
+#[cfg(feature = "http")]
+pub mod remote;
 pub mod schema;
 pub mod session;
+pub mod source;
 pub mod store;
 pub mod trading_calendar;
+pub mod validation;
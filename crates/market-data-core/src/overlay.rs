@@ -0,0 +1,338 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use bytes::Bytes;
+
+use crate::backend::{LocalBackend, ObjectMeta, StorageBackend};
+use crate::error::MarketDataError;
+use crate::store::CandleStore;
+
+/// Builds a [`CandleStore`] that layers several data roots.
+///
+/// Reads resolve each day file from the first layer that has it, so a layer
+/// added earlier shadows the same day in later layers; listings are the union
+/// of all layers. Writes and deletes go to the single writable layer; a delete
+/// fails when a read-only layer holds the key, since it would stay visible.
+/// Store operations that replace day files with packs (`write_daily`,
+/// `compact`) therefore fail rather than leave a read-only day file
+/// shadowing the new pack.
+///
+/// ```no_run
+/// use market_data_core::overlay::OverlayStore;
+///
+/// let store = OverlayStore::new()
+///     .writable_root("../private-market-data")
+///     .root("../market-data")
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct OverlayStore {
+    layers: Vec<Arc<dyn StorageBackend>>,
+    writable: Option<usize>,
+}
+
+impl OverlayStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a read-only layer rooted at `root`; its `data/` subdirectory is
+    /// used, as with [`CandleStore::new`].
+    pub fn root(self, root: impl AsRef<Path>) -> Self {
+        self.backend(Arc::new(LocalBackend::new(root.as_ref().join("data"))))
+    }
+
+    /// Add the writable layer rooted at `root`.
+    pub fn writable_root(self, root: impl AsRef<Path>) -> Self {
+        self.writable_backend(Arc::new(LocalBackend::new(root.as_ref().join("data"))))
+    }
+
+    /// Add a read-only layer over any backend.
+    pub fn backend(mut self, backend: Arc<dyn StorageBackend>) -> Self {
+        self.layers.push(backend);
+        self
+    }
+
+    /// Add the writable layer over any backend. Replaces any previously
+    /// designated writable layer, which stays in place as read-only.
+    pub fn writable_backend(mut self, backend: Arc<dyn StorageBackend>) -> Self {
+        self.writable = Some(self.layers.len());
+        self.layers.push(backend);
+        self
+    }
+
+    pub fn into_backend(self) -> OverlayBackend {
+        OverlayBackend {
+            layers: self.layers,
+            writable: self.writable,
+        }
+    }
+
+    pub fn build(self) -> CandleStore {
+        CandleStore::with_backend(Arc::new(self.into_backend()))
+    }
+}
+
+/// [`StorageBackend`] behind an [`OverlayStore`], shadowing per key.
+#[derive(Debug)]
+pub struct OverlayBackend {
+    layers: Vec<Arc<dyn StorageBackend>>,
+    writable: Option<usize>,
+}
+
+impl OverlayBackend {
+    /// Index of the layer that serves `key`, in the order layers were added.
+    pub fn layer_of(&self, key: &str) -> Result<Option<usize>, MarketDataError> {
+        for (i, layer) in self.layers.iter().enumerate() {
+            if layer.head(key)?.is_some() {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    fn writable(&self) -> Result<&Arc<dyn StorageBackend>, MarketDataError> {
+        self.writable
+            .map(|i| &self.layers[i])
+            .ok_or_else(|| MarketDataError::Storage("overlay has no writable layer".to_string()))
+    }
+
+    /// Sorted, de-duplicated union of a listing across all layers.
+    fn union(
+        &self,
+        list: impl Fn(&dyn StorageBackend) -> Result<Vec<String>, MarketDataError>,
+    ) -> Result<Vec<String>, MarketDataError> {
+        let mut all = Vec::new();
+        for layer in &self.layers {
+            all.extend(list(layer.as_ref())?);
+        }
+        all.sort();
+        all.dedup();
+        Ok(all)
+    }
+}
+
+impl StorageBackend for OverlayBackend {
+    fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+        for layer in &self.layers {
+            if let Some(data) = layer.get(key)? {
+                return Ok(Some(data));
+            }
+        }
+        Ok(None)
+    }
+
+    fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+        for layer in &self.layers {
+            if let Some(meta) = layer.head(key)? {
+                return Ok(Some(meta));
+            }
+        }
+        Ok(None)
+    }
+
+    fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+        self.writable()?.put(key, data)
+    }
+
+    /// Deletes from the writable layer. Fails without deleting anything when
+    /// a read-only layer also holds `key`, which the delete can't remove.
+    fn delete(&self, key: &str) -> Result<(), MarketDataError> {
+        let writable = self.writable()?;
+        for (i, layer) in self.layers.iter().enumerate() {
+            if Some(i) != self.writable && layer.head(key)?.is_some() {
+                return Err(MarketDataError::Storage(format!(
+                    "{key} is held by read-only overlay layer {i}"
+                )));
+            }
+        }
+        writable.delete(key)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        self.union(|layer| layer.list(prefix))
+    }
+
+    fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        self.union(|layer| layer.list_prefixes(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MemoryBackend;
+    use crate::candle::Candle;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, d).unwrap()
+    }
+
+    fn candles(day: u32, close: Decimal) -> Vec<Candle> {
+        vec![Candle {
+            timestamp: Utc.with_ymd_and_hms(2025, 1, day, 14, 30, 0).unwrap(),
+            open: dec!(150.00),
+            high: dec!(151.00),
+            low: dec!(149.00),
+            close,
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        }]
+    }
+
+    #[test]
+    fn earlier_layers_shadow_later_ones() {
+        let public = CandleStore::with_backend(Arc::new(MemoryBackend::new()));
+        for day in [14, 15, 16] {
+            public
+                .write_day("AAPL", date(day), &candles(day, dec!(150.50)))
+                .unwrap();
+        }
+        public
+            .write_day("SPY", date(15), &candles(15, dec!(590.00)))
+            .unwrap();
+
+        let private = Arc::new(MemoryBackend::new());
+        let store = OverlayStore::new()
+            .writable_backend(private.clone())
+            .backend(public.backend().clone())
+            .build();
+
+        // A corrected day and a private symbol go to the writable layer
+        store
+            .write_day("AAPL", date(15), &candles(15, dec!(150.75)))
+            .unwrap();
+        store
+            .write_day("PRIV", date(17), &candles(17, dec!(10.00)))
+            .unwrap();
+        assert_eq!(private.list("").unwrap().len(), 2);
+        assert_eq!(public.list_symbols().unwrap(), vec!["AAPL", "SPY"]);
+
+        assert_eq!(store.list_symbols().unwrap(), vec!["AAPL", "PRIV", "SPY"]);
+        assert_eq!(
+            store.list_dates("AAPL").unwrap(),
+            vec![date(14), date(15), date(16)]
+        );
+        let closes: Vec<Decimal> = store
+            .read_range("AAPL", date(14), date(16))
+            .unwrap()
+            .iter()
+            .map(|c| c.close)
+            .collect();
+        assert_eq!(closes, vec![dec!(150.50), dec!(150.75), dec!(150.50)]);
+
+        // The overlay can't delete a day a read-only layer still holds
+        let key = CandleStore::key("AAPL", date(15));
+        assert!(matches!(
+            store.backend().delete(&key),
+            Err(MarketDataError::Storage(_))
+        ));
+        assert!(private.head(&key).unwrap().is_some());
+
+        // Deleting the correction from the writable layer reveals the public
+        // day again
+        private.delete(&key).unwrap();
+        assert_eq!(
+            store.read_day("AAPL", date(15)).unwrap()[0].close,
+            dec!(150.50)
+        );
+    }
+
+    #[test]
+    fn daily_write_over_read_only_day_file_fails() {
+        let public = CandleStore::with_backend(Arc::new(MemoryBackend::new()));
+        public
+            .write_day("AAPL", date(15), &candles(15, dec!(150.50)))
+            .unwrap();
+
+        let private = Arc::new(MemoryBackend::new());
+        let store = OverlayStore::new()
+            .writable_backend(private.clone())
+            .backend(public.backend().clone())
+            .build();
+
+        // The new pack would be shadowed by the public day file
+        assert!(matches!(
+            store.write_daily("AAPL", &candles(15, dec!(150.75))),
+            Err(MarketDataError::Storage(_))
+        ));
+        assert!(public.has_data("AAPL", date(15)));
+        assert_eq!(
+            store.read_day("AAPL", date(15)).unwrap()[0].close,
+            dec!(150.50)
+        );
+
+        // Days only the writable layer holds are still replaced by the pack
+        store
+            .write_day("AAPL", date(16), &candles(16, dec!(151.00)))
+            .unwrap();
+        store
+            .write_daily("AAPL", &candles(16, dec!(151.25)))
+            .unwrap();
+        assert!(
+            private
+                .head(&CandleStore::key("AAPL", date(16)))
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            store.read_day("AAPL", date(16)).unwrap()[0].close,
+            dec!(151.25)
+        );
+    }
+
+    #[test]
+    fn layer_of_reports_serving_layer() {
+        let first = Arc::new(MemoryBackend::new());
+        let second = Arc::new(MemoryBackend::new());
+        first.put("AAPL/a", Bytes::new()).unwrap();
+        second.put("AAPL/a", Bytes::new()).unwrap();
+        second.put("AAPL/b", Bytes::new()).unwrap();
+
+        let overlay = OverlayStore::new()
+            .backend(first)
+            .backend(second)
+            .into_backend();
+        assert_eq!(overlay.layer_of("AAPL/a").unwrap(), Some(0));
+        assert_eq!(overlay.layer_of("AAPL/b").unwrap(), Some(1));
+        assert_eq!(overlay.layer_of("AAPL/c").unwrap(), None);
+    }
+
+    #[test]
+    fn read_only_overlay_rejects_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = OverlayStore::new().root(dir.path()).build();
+        assert!(matches!(
+            store.write_day("AAPL", date(15), &candles(15, dec!(1))),
+            Err(MarketDataError::Storage(_))
+        ));
+    }
+
+    #[test]
+    fn filesystem_roots() {
+        let public = tempfile::tempdir().unwrap();
+        let private = tempfile::tempdir().unwrap();
+        CandleStore::new(public.path())
+            .write_day("AAPL", date(15), &candles(15, dec!(150.50)))
+            .unwrap();
+
+        let store = OverlayStore::new()
+            .writable_root(private.path())
+            .root(public.path())
+            .build();
+        store
+            .write_day("AAPL", date(16), &candles(16, dec!(151.00)))
+            .unwrap();
+
+        assert!(CandleStore::new(private.path()).has_data("AAPL", date(16)));
+        assert!(!CandleStore::new(public.path()).has_data("AAPL", date(16)));
+        assert_eq!(
+            store.read_range("AAPL", date(15), date(16)).unwrap().len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::candle::Candle;
+use crate::error::MarketDataError;
+
+/// Largest scale a [`Decimal`] can hold.
+const MAX_SCALE: u32 = 28;
+
+/// How the prices of one symbol are rounded before they are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceRule {
+    /// Decimal places kept, e.g. 2 for whole cents.
+    pub scale: u32,
+    /// Round to a multiple of this tick after applying `scale`, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick_size: Option<Decimal>,
+}
+
+impl PriceRule {
+    /// Round to `scale` decimal places, with no tick size.
+    pub fn scale(scale: u32) -> Self {
+        Self {
+            scale,
+            tick_size: None,
+        }
+    }
+
+    /// Round a price to this rule, with midpoints rounded away from zero.
+    /// Trailing zeros are dropped, so the same price from different sources
+    /// is stored as the same string.
+    pub fn apply(&self, price: Decimal) -> Decimal {
+        let round = |price: Decimal, dp: u32| {
+            price.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero)
+        };
+        let mut price = round(price, self.scale);
+        if let Some(tick) = self.tick_size {
+            price = round(round(price / tick, 0) * tick, self.scale);
+        }
+        price.normalize()
+    }
+
+    fn validate(&self) -> Result<(), MarketDataError> {
+        if self.scale > MAX_SCALE {
+            return Err(MarketDataError::InvalidData(format!(
+                "price scale must be at most {MAX_SCALE}, got {}",
+                self.scale
+            )));
+        }
+        if let Some(tick) = self.tick_size
+            && tick <= Decimal::ZERO
+        {
+            return Err(MarketDataError::InvalidData(format!(
+                "tick_size must be positive, got {tick}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for PriceRule {
+    /// Four decimal places: sub-penny prices survive, float noise does not.
+    fn default() -> Self {
+        Self::scale(4)
+    }
+}
+
+/// Price rounding applied to every candle a store writes. See
+/// [`StoreConfig::prices`](crate::config::StoreConfig::prices).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriceRules {
+    /// Rule for symbols without their own entry.
+    pub default: PriceRule,
+    /// Per-symbol rules, keyed by uppercase symbol.
+    #[serde(
+        skip_serializing_if = "BTreeMap::is_empty",
+        deserialize_with = "uppercase_symbols"
+    )]
+    pub symbols: BTreeMap<String, PriceRule>,
+}
+
+/// Uppercase the symbol keys of a hand-edited `store.json`, so lookups match
+/// them. Two keys that differ only in case are rejected.
+fn uppercase_symbols<'de, D>(deserializer: D) -> Result<BTreeMap<String, PriceRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut symbols = BTreeMap::new();
+    for (symbol, rule) in BTreeMap::<String, PriceRule>::deserialize(deserializer)? {
+        let symbol = symbol.to_uppercase();
+        if symbols.insert(symbol.clone(), rule).is_some() {
+            return Err(serde::de::Error::custom(format!(
+                "duplicate price rule for {symbol}"
+            )));
+        }
+    }
+    Ok(symbols)
+}
+
+impl PriceRules {
+    /// Add or replace the rule for one symbol.
+    pub fn with_symbol(mut self, symbol: &str, rule: PriceRule) -> Self {
+        self.symbols.insert(symbol.to_uppercase(), rule);
+        self
+    }
+
+    /// Rule that applies to `symbol`.
+    pub fn rule(&self, symbol: &str) -> &PriceRule {
+        self.symbols
+            .get(&symbol.to_uppercase())
+            .unwrap_or(&self.default)
+    }
+
+    /// Round the open, high, low and close of each candle with the symbol's
+    /// rule. VWAP is an average rather than a traded price and keeps its
+    /// precision, with trailing zeros dropped.
+    pub fn apply(&self, symbol: &str, candles: &mut [Candle]) {
+        let rule = self.rule(symbol);
+        for candle in candles {
+            candle.open = rule.apply(candle.open);
+            candle.high = rule.apply(candle.high);
+            candle.low = rule.apply(candle.low);
+            candle.close = rule.apply(candle.close);
+            candle.vwap = candle.vwap.map(|v| v.normalize());
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), MarketDataError> {
+        self.default.validate()?;
+        for (symbol, rule) in &self.symbols {
+            rule.validate()
+                .map_err(|e| MarketDataError::InvalidData(format!("{symbol}: {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn float_noise_is_rounded_away() {
+        let rule = PriceRule::default();
+        let noisy = dec!(150.11999511718750);
+        assert_eq!(rule.apply(noisy), dec!(150.12));
+        assert_eq!(rule.apply(noisy).to_string(), "150.12");
+        assert_eq!(rule.apply(dec!(0.12345)).to_string(), "0.1235");
+        assert_eq!(rule.apply(dec!(17.240000)).to_string(), "17.24");
+    }
+
+    #[test]
+    fn tick_size_and_symbol_rules() {
+        let nickel = PriceRule {
+            scale: 2,
+            tick_size: Some(dec!(0.05)),
+        };
+        assert_eq!(nickel.apply(dec!(15.82)), dec!(15.80));
+        assert_eq!(nickel.apply(dec!(15.83)), dec!(15.85));
+        assert_eq!(nickel.apply(dec!(15.825)), dec!(15.85));
+        assert_eq!(nickel.apply(dec!(-15.825)), dec!(-15.85));
+
+        let rules = PriceRules::default().with_symbol("vix", nickel);
+        assert_eq!(rules.rule("VIX"), &nickel);
+        assert_eq!(rules.rule("AAPL"), &PriceRule::default());
+
+        let mut invalid = rules.clone().with_symbol("SPY", PriceRule::scale(29));
+        assert!(invalid.validate().is_err());
+        invalid.symbols.remove("SPY");
+        invalid.default.tick_size = Some(Decimal::ZERO);
+        assert!(invalid.validate().is_err());
+        assert!(rules.validate().is_ok());
+    }
+
+    #[test]
+    fn midpoints_round_away_from_zero() {
+        let cents = PriceRule::scale(2);
+        assert_eq!(cents.apply(dec!(1.125)), dec!(1.13));
+        assert_eq!(cents.apply(dec!(1.135)), dec!(1.14));
+        assert_eq!(cents.apply(dec!(-1.125)), dec!(-1.13));
+    }
+
+    #[test]
+    fn loaded_symbol_keys_are_uppercased() {
+        let rules: PriceRules =
+            serde_json::from_str(r#"{"symbols": {"vix": {"scale": 2}}}"#).unwrap();
+        assert_eq!(rules.rule("VIX"), &PriceRule::scale(2));
+        assert!(rules.symbols.contains_key("VIX"));
+
+        let duplicate = r#"{"symbols": {"vix": {"scale": 2}, "VIX": {"scale": 3}}}"#;
+        assert!(serde_json::from_str::<PriceRules>(duplicate).is_err());
+    }
+}
@@ -0,0 +1,718 @@
+use std::ops::{Bound, RangeBounds};
+
+use arrow::record_batch::RecordBatch;
+use chrono::offset::LocalResult;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::America::New_York;
+
+use crate::candle::{Candle, Timeframe};
+use crate::error::MarketDataError;
+use crate::schema;
+use crate::session::{self, Session};
+use crate::store::{CandleStore, DayIndex};
+use crate::trading_calendar;
+
+/// Composable read query over a single symbol in a [`CandleStore`].
+///
+/// Built with [`CandleStore::query`]. All filters are applied in a single pass
+/// over the day files, and a `limit` stops the scan as soon as enough candles
+/// have been collected.
+///
+/// ```no_run
+/// # use market_data_core::store::CandleStore;
+/// # use market_data_core::session::Session;
+/// # use chrono::{NaiveDate, NaiveTime};
+/// let store = CandleStore::new(".");
+/// let candles = store
+///     .query("AAPL")
+///     .between(
+///         NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+///         NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+///     )
+///     .sessions([Session::PreMarket, Session::Regular])
+///     .time_of_day(
+///         NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+///         NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+///     )
+///     .limit(20)
+///     .descending()
+///     .collect()?;
+/// # Ok::<(), market_data_core::error::MarketDataError>(())
+/// ```
+pub struct Query<'a> {
+    store: &'a CandleStore,
+    symbol: String,
+    dates: Option<(NaiveDate, NaiveDate)>,
+    weekdays: bool,
+    filter: CandleFilter,
+    limit: Option<usize>,
+    descending: bool,
+}
+
+impl<'a> Query<'a> {
+    pub(crate) fn new(store: &'a CandleStore, symbol: &str) -> Self {
+        Self {
+            store,
+            symbol: symbol.to_string(),
+            dates: None,
+            weekdays: false,
+            filter: CandleFilter::default(),
+            limit: None,
+            descending: false,
+        }
+    }
+
+    /// Restrict to day files dated within `[start, end]` (inclusive).
+    /// Without this, every date with data for the symbol is scanned. Weekend
+    /// dates are kept: a Friday's late evening ET bars are stored under the
+    /// Saturday UTC date.
+    pub fn between(mut self, start: NaiveDate, end: NaiveDate) -> Self {
+        self.dates = Some((start, end));
+        self
+    }
+
+    /// Skip day files dated on a weekend, as [`CandleStore::read_range`] does.
+    pub(crate) fn weekdays(mut self) -> Self {
+        self.weekdays = true;
+        self
+    }
+
+    /// Keep only candles whose timestamp falls within `range`, e.g.
+    /// `..before` for everything strictly before a point in time, or
+    /// `(Bound::Excluded(after), Bound::Unbounded)` for everything after it.
+    /// Day files entirely outside the range are not read.
+    pub fn timestamps(mut self, range: impl RangeBounds<DateTime<Utc>>) -> Self {
+        self.filter.timestamps = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Keep only candles in one of the given sessions.
+    pub fn sessions(mut self, sessions: impl IntoIterator<Item = Session>) -> Self {
+        self.filter.sessions = Some(sessions.into_iter().collect());
+        self
+    }
+
+    /// Keep only candles in the given session.
+    pub fn session(self, session: Session) -> Self {
+        self.sessions([session])
+    }
+
+    /// Keep only candles whose exchange-local (ET) time of day falls within
+    /// `[start, end]` (inclusive).
+    pub fn time_of_day(mut self, start: NaiveTime, end: NaiveTime) -> Self {
+        self.filter.time_of_day = Some((start, end));
+        self
+    }
+
+    /// Return at most `n` candles.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    /// Return candles newest first. Combined with `limit`, this yields the
+    /// most recent matching candles.
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    /// Run the query and collect all matching candles.
+    pub fn collect(self) -> Result<Vec<Candle>, MarketDataError> {
+        self.iter()?.collect()
+    }
+
+    /// Run the query, skipping day files that cannot be read or decoded
+    /// instead of failing the whole scan. Skipped files are listed in the
+    /// returned [`ReadReport`]; listing errors still fail the query.
+    pub fn collect_lenient(self) -> Result<(Vec<Candle>, ReadReport), MarketDataError> {
+        let symbol = self.symbol.clone();
+        let mut days = self.scan()?;
+
+        let mut candles = Vec::new();
+        let mut report = ReadReport::default();
+        while let Some((date, day)) = days.next_day() {
+            match day {
+                Ok(day) => candles.extend(day),
+                Err(error) => report.skipped.push(SkippedFile {
+                    key: days
+                        .index
+                        .key(date)
+                        .map_or_else(|| CandleStore::key(&symbol, date), str::to_string),
+                    symbol: symbol.clone(),
+                    date,
+                    error,
+                }),
+            }
+        }
+        Ok((candles, report))
+    }
+
+    /// Run the query lazily, reading one day file at a time.
+    pub fn iter(self) -> Result<CandleIter<'a>, MarketDataError> {
+        Ok(CandleIter {
+            days: self.scan()?,
+            current: Vec::new().into_iter(),
+        })
+    }
+
+    /// Run the query and return matching candles as Arrow record batches,
+    /// one batch per day file.
+    pub fn record_batches(self) -> Result<Vec<RecordBatch>, MarketDataError> {
+        self.scan()?
+            .map(|day| schema::candles_to_record_batch(&day?))
+            .collect()
+    }
+
+    fn scan(self) -> Result<DayScan<'a>, MarketDataError> {
+        // Resolve every day's file up front, so each pack is fetched once per
+        // scan rather than once per day. Packs outside the timestamp bounds are
+        // never fetched.
+        let range = match (self.dates, self.filter.date_range()) {
+            (Some((start, end)), Some((lo, hi))) => Some((start.max(lo), end.min(hi))),
+            (dates, bounds) => dates.or(bounds),
+        };
+        let index = self.store.index_days(&self.symbol, range)?;
+        let mut dates: Vec<NaiveDate> = index
+            .dates()
+            .filter(|d| !self.weekdays || trading_calendar::is_weekday(*d))
+            .filter(|d| self.filter.may_contain(*d))
+            .collect();
+        if self.descending {
+            dates.reverse();
+        }
+        Ok(DayScan {
+            index,
+            remaining: self.limit,
+            dates: dates.into_iter(),
+            query: self,
+        })
+    }
+}
+
+/// Day files skipped by a lenient read. See [`Query::collect_lenient`].
+#[derive(Debug, Default)]
+pub struct ReadReport {
+    pub skipped: Vec<SkippedFile>,
+}
+
+impl ReadReport {
+    /// True if every day file in the scan was read.
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+/// A day file that could not be read, and why.
+#[derive(Debug)]
+pub struct SkippedFile {
+    pub symbol: String,
+    pub date: NaiveDate,
+    /// Backend key of the file, e.g. `AAPL/2025/01/2025-01-15.parquet`.
+    pub key: String,
+    pub error: MarketDataError,
+}
+
+/// Timestamp-only predicate shared by all query filters. Cheap to clone so it
+/// can be handed to the Parquet reader as a row filter.
+#[derive(Debug, Clone)]
+pub(crate) struct CandleFilter {
+    timestamps: (Bound<DateTime<Utc>>, Bound<DateTime<Utc>>),
+    sessions: Option<Vec<Session>>,
+    time_of_day: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl Default for CandleFilter {
+    fn default() -> Self {
+        Self {
+            timestamps: (Bound::Unbounded, Bound::Unbounded),
+            sessions: None,
+            time_of_day: None,
+        }
+    }
+}
+
+impl CandleFilter {
+    /// Filter on timestamp bounds only.
+    pub(crate) fn within(range: impl RangeBounds<DateTime<Utc>>) -> Self {
+        Self {
+            timestamps: (range.start_bound().cloned(), range.end_bound().cloned()),
+            ..Self::default()
+        }
+    }
+
+    /// True when no filter is set and every candle matches.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.timestamps == (Bound::Unbounded, Bound::Unbounded)
+            && self.sessions.is_none()
+            && self.time_of_day.is_none()
+    }
+
+    pub(crate) fn matches(&self, timestamp: &DateTime<Utc>) -> bool {
+        if !self.timestamps.contains(timestamp) {
+            return false;
+        }
+        if let Some(sessions) = &self.sessions {
+            match Session::classify(timestamp) {
+                Some(s) if sessions.contains(&s) => {}
+                _ => return false,
+            }
+        }
+        if let Some((start, end)) = self.time_of_day {
+            let time = session::to_exchange_time(timestamp).time();
+            if time < start || time > end {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// [`CandleFilter::matches`] for a bar of the given timeframe. A daily bar
+    /// stands for the whole regular session of its date: it matches a session
+    /// filter that includes [`Session::Regular`], and a time-of-day window
+    /// that overlaps regular hours.
+    pub(crate) fn matches_bar(&self, start: &DateTime<Utc>, timeframe: Timeframe) -> bool {
+        if timeframe == Timeframe::FiveMinute {
+            return self.matches(start);
+        }
+        if !self.timestamps.contains(start) {
+            return false;
+        }
+        if let Some(sessions) = &self.sessions {
+            match Session::classify_bar(start, timeframe) {
+                Some(s) if sessions.contains(&s) => {}
+                _ => return false,
+            }
+        }
+        if let Some((from, to)) = self.time_of_day {
+            let (open, close) = Session::Regular.hours();
+            if to < open || from >= close {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Days whose files can hold candles inside the timestamp bounds, widened
+    /// by a day on each side. `None` if neither bound is set.
+    fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let start = match self.timestamps.0 {
+            Bound::Included(ts) | Bound::Excluded(ts) => ts.date_naive().pred_opt(),
+            Bound::Unbounded => None,
+        };
+        let end = match self.timestamps.1 {
+            Bound::Included(ts) | Bound::Excluded(ts) => ts.date_naive().succ_opt(),
+            Bound::Unbounded => None,
+        };
+        (start.is_some() || end.is_some()).then(|| {
+            (
+                start.unwrap_or(NaiveDate::MIN),
+                end.unwrap_or(NaiveDate::MAX),
+            )
+        })
+    }
+
+    /// Whether the day file for `date` can hold candles inside the timestamp bounds.
+    fn may_contain(&self, date: NaiveDate) -> bool {
+        let after_start = match self.timestamps.0 {
+            Bound::Included(ts) | Bound::Excluded(ts) => date >= ts.date_naive(),
+            Bound::Unbounded => true,
+        };
+        let before_end = match self.timestamps.1 {
+            Bound::Included(ts) | Bound::Excluded(ts) => date <= ts.date_naive(),
+            Bound::Unbounded => true,
+        };
+        after_start && before_end
+    }
+
+    /// Inclusive UTC windows covering every candle in the day file for `date`
+    /// that can match, used to prune row groups and pages.
+    pub(crate) fn ranges(&self, date: NaiveDate) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let lower = match self.timestamps.0 {
+            Bound::Included(ts) | Bound::Excluded(ts) => ts,
+            Bound::Unbounded => DateTime::<Utc>::MIN_UTC,
+        };
+        let upper = match self.timestamps.1 {
+            Bound::Included(ts) | Bound::Excluded(ts) => ts,
+            Bound::Unbounded => DateTime::<Utc>::MAX_UTC,
+        };
+
+        if self.sessions.is_none() && self.time_of_day.is_none() {
+            return vec![(lower, upper)];
+        }
+
+        // Exchange-local windows, intersected with the time-of-day filter
+        let mut windows: Vec<(NaiveTime, NaiveTime)> = match &self.sessions {
+            Some(sessions) => sessions.iter().map(|s| s.hours()).collect(),
+            None => vec![(NaiveTime::MIN, end_of_day())],
+        };
+        if let Some((start, end)) = self.time_of_day {
+            windows = windows
+                .into_iter()
+                .map(|(s, e)| (s.max(start), e.min(end)))
+                .filter(|(s, e)| s <= e)
+                .collect();
+        }
+
+        // A UTC day file holds the evening of the previous ET date
+        let et_dates = [date.pred_opt().unwrap_or(date), date];
+        et_dates
+            .iter()
+            .flat_map(|d| {
+                windows.iter().map(move |&(s, e)| {
+                    (
+                        exchange_to_utc(d.and_time(s), true),
+                        exchange_to_utc(d.and_time(e), false),
+                    )
+                })
+            })
+            .map(|(lo, hi)| (lo.max(lower), hi.min(upper)))
+            .filter(|(lo, hi)| lo <= hi)
+            .collect()
+    }
+}
+
+fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_micro_opt(23, 59, 59, 999_999).unwrap()
+}
+
+/// UTC instant for an ET wall-clock time. Times skipped by a DST transition
+/// are widened by an hour in the direction given by `is_start`, so windows
+/// built from them never exclude a matching candle.
+pub(crate) fn exchange_to_utc(local: NaiveDateTime, is_start: bool) -> DateTime<Utc> {
+    match New_York.from_local_datetime(&local) {
+        LocalResult::Single(t) => t.with_timezone(&Utc),
+        LocalResult::Ambiguous(early, late) => {
+            if is_start {
+                early.with_timezone(&Utc)
+            } else {
+                late.with_timezone(&Utc)
+            }
+        }
+        LocalResult::None => {
+            let shift = if is_start {
+                -Duration::hours(1)
+            } else {
+                Duration::hours(1)
+            };
+            exchange_to_utc(local + shift, is_start)
+        }
+    }
+}
+
+/// Iterator over the filtered candles of each day file, in scan order.
+struct DayScan<'a> {
+    query: Query<'a>,
+    index: DayIndex,
+    dates: std::vec::IntoIter<NaiveDate>,
+    remaining: Option<usize>,
+}
+
+impl DayScan<'_> {
+    /// Read the next non-empty day, returning its date with the result so a
+    /// lenient caller can record which file failed.
+    fn next_day(&mut self) -> Option<(NaiveDate, Result<Vec<Candle>, MarketDataError>)> {
+        loop {
+            if self.remaining == Some(0) {
+                return None;
+            }
+            let date = self.dates.next()?;
+            let read = self.query.store.read_indexed(
+                &self.query.symbol,
+                date,
+                &self.query.filter,
+                Some(&self.index),
+            );
+            let mut candles = match read {
+                Ok(candles) => candles,
+                Err(MarketDataError::NoData { .. }) => continue,
+                Err(e) => return Some((date, Err(e))),
+            };
+
+            candles.sort_by_key(|c| c.timestamp);
+            if self.query.descending {
+                candles.reverse();
+            }
+            if let Some(remaining) = self.remaining.as_mut() {
+                candles.truncate(*remaining);
+                *remaining -= candles.len();
+            }
+
+            if !candles.is_empty() {
+                return Some((date, Ok(candles)));
+            }
+        }
+    }
+}
+
+impl Iterator for DayScan<'_> {
+    type Item = Result<Vec<Candle>, MarketDataError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_day().map(|(_, day)| day)
+    }
+}
+
+/// Lazy iterator over query results. See [`Query::iter`].
+pub struct CandleIter<'a> {
+    days: DayScan<'a>,
+    current: std::vec::IntoIter<Candle>,
+}
+
+impl Iterator for CandleIter<'_> {
+    type Item = Result<Candle, MarketDataError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(candle) = self.current.next() {
+                return Some(Ok(candle));
+            }
+            match self.days.next()? {
+                Ok(day) => self.current = day.into_iter(),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Timelike, Utc};
+    use rust_decimal_macros::dec;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn make_candle(day: u32, hour: u32, min: u32) -> Candle {
+        Candle {
+            timestamp: Utc.with_ymd_and_hms(2025, 1, day, hour, min, 0).unwrap(),
+            open: dec!(150.00),
+            high: dec!(151.00),
+            low: dec!(149.00),
+            close: dec!(150.50),
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        }
+    }
+
+    /// Jan 15-17, 2025 (EST): 13:00 UTC = 8:00 ET (PreMarket),
+    /// 15:00 UTC = 10:00 ET (Regular), 22:00 UTC = 17:00 ET (AfterHours).
+    fn populated_store(dir: &std::path::Path) -> CandleStore {
+        let store = CandleStore::new(dir);
+        for day in 15..=17 {
+            store
+                .write_day(
+                    "AAPL",
+                    date(2025, 1, day),
+                    &[
+                        make_candle(day, 13, 0),
+                        make_candle(day, 15, 0),
+                        make_candle(day, 22, 0),
+                    ],
+                )
+                .unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn unfiltered_query_returns_everything_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+
+        let result = store.query("AAPL").collect().unwrap();
+        assert_eq!(result.len(), 9);
+        assert!(result.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[test]
+    fn between_restricts_dates() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+
+        let result = store
+            .query("AAPL")
+            .between(date(2025, 1, 16), date(2025, 1, 20))
+            .collect()
+            .unwrap();
+        assert_eq!(result.len(), 6);
+    }
+
+    #[test]
+    fn multiple_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+
+        let result = store
+            .query("AAPL")
+            .sessions([Session::PreMarket, Session::AfterHours])
+            .collect()
+            .unwrap();
+        assert_eq!(result.len(), 6);
+        assert!(result.iter().all(|c| c.timestamp.hour() != 15));
+    }
+
+    #[test]
+    fn time_of_day_uses_exchange_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+
+        let result = store
+            .query("AAPL")
+            .time_of_day(
+                NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+                NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            )
+            .collect()
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|c| c.timestamp.hour() == 15));
+    }
+
+    #[test]
+    fn descending_with_limit_returns_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+
+        let result = store
+            .query("AAPL")
+            .session(Session::Regular)
+            .limit(2)
+            .descending()
+            .collect()
+            .unwrap();
+        assert_eq!(
+            result.iter().map(|c| c.timestamp).collect::<Vec<_>>(),
+            vec![
+                Utc.with_ymd_and_hms(2025, 1, 17, 15, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 16, 15, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn iter_is_lazy_and_honors_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+
+        let first: Vec<Candle> = store
+            .query("AAPL")
+            .limit(4)
+            .iter()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(first.len(), 4);
+        assert_eq!(first[3].timestamp.day(), 16);
+    }
+
+    #[test]
+    fn record_batches_one_per_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+
+        let batches = store
+            .query("AAPL")
+            .session(Session::Regular)
+            .record_batches()
+            .unwrap();
+        assert_eq!(batches.len(), 3);
+        assert!(batches.iter().all(|b| b.num_rows() == 1));
+    }
+
+    #[test]
+    fn timestamps_bound_is_exclusive_when_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+        let cutoff = Utc.with_ymd_and_hms(2025, 1, 16, 15, 0, 0).unwrap();
+
+        let before = store.query("AAPL").timestamps(..cutoff).collect().unwrap();
+        assert_eq!(before.len(), 4);
+        assert!(before.iter().all(|c| c.timestamp < cutoff));
+
+        let through = store.query("AAPL").timestamps(..=cutoff).collect().unwrap();
+        assert_eq!(through.len(), 5);
+
+        let after = store
+            .query("AAPL")
+            .timestamps((Bound::Excluded(cutoff), Bound::Unbounded))
+            .collect()
+            .unwrap();
+        assert_eq!(after.len(), 4);
+    }
+
+    #[test]
+    fn ranges_cover_session_in_utc() {
+        let filter = CandleFilter {
+            sessions: Some(vec![Session::Regular]),
+            ..CandleFilter::default()
+        };
+        // Summer (EDT): Regular is 13:30-20:00 UTC; the previous ET date's
+        // window lies entirely on the previous UTC day.
+        let ranges = filter.ranges(date(2025, 7, 15));
+        assert!(ranges.contains(&(
+            Utc.with_ymd_and_hms(2025, 7, 15, 13, 30, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 7, 15, 20, 0, 0).unwrap(),
+        )));
+    }
+
+    #[test]
+    fn ranges_intersect_time_of_day_and_bounds() {
+        let cutoff = Utc.with_ymd_and_hms(2025, 1, 15, 15, 10, 0).unwrap();
+        let filter = CandleFilter {
+            timestamps: (Bound::Unbounded, Bound::Excluded(cutoff)),
+            sessions: Some(vec![Session::Regular]),
+            time_of_day: Some((
+                NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(11, 0, 0).unwrap(),
+            )),
+        };
+        let ranges = filter.ranges(date(2025, 1, 15));
+        assert_eq!(
+            ranges.last(),
+            Some(&(Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap(), cutoff))
+        );
+    }
+
+    #[test]
+    fn exchange_to_utc_widens_dst_gap() {
+        // 2:30 ET does not exist on Mar 9, 2025
+        let local = date(2025, 3, 9).and_hms_opt(2, 30, 0).unwrap();
+        assert!(exchange_to_utc(local, true) <= Utc.with_ymd_and_hms(2025, 3, 9, 7, 0, 0).unwrap());
+        assert!(
+            exchange_to_utc(local, false) >= Utc.with_ymd_and_hms(2025, 3, 9, 7, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn missing_symbol_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        assert!(store.query("AAPL").collect().unwrap().is_empty());
+    }
+
+    #[test]
+    fn lenient_collect_skips_unreadable_days() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = populated_store(dir.path());
+        std::fs::write(store.file_path("AAPL", date(2025, 1, 16)), b"not parquet").unwrap();
+
+        assert!(store.query("AAPL").collect().is_err());
+
+        let (candles, report) = store
+            .query("AAPL")
+            .session(Session::Regular)
+            .collect_lenient()
+            .unwrap();
+        assert_eq!(candles.len(), 2);
+        assert!(!report.is_complete());
+        assert_eq!(report.skipped.len(), 1);
+        let skipped = &report.skipped[0];
+        assert_eq!(skipped.date, date(2025, 1, 16));
+        assert_eq!(skipped.key, "AAPL/2025/01/2025-01-16.parquet");
+        assert!(matches!(skipped.error, MarketDataError::Parquet(_)));
+    }
+}
@@ -0,0 +1,546 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use bytes::Bytes;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{LocalBackend, ObjectMeta, StorageBackend};
+use crate::candle::Candle;
+use crate::error::MarketDataError;
+use crate::source::CandleSource;
+use crate::store::CandleStore;
+
+/// Name of the index files, at the data root and in each symbol directory.
+pub const INDEX_FILE: &str = "index.json";
+
+/// Root index: the symbols present in the store.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreIndex {
+    pub symbols: Vec<String>,
+}
+
+/// Per-symbol index: every data file's key and size in bytes, and a content
+/// hash of each file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolIndex {
+    pub files: BTreeMap<String, u64>,
+    /// Hex xxHash64 of each file's contents. Indexes written before hashes
+    /// were recorded lack it, and their files are never served from a disk
+    /// cache.
+    #[serde(default)]
+    pub hashes: BTreeMap<String, String>,
+}
+
+fn content_hash(data: &[u8]) -> String {
+    format!("{:016x}", twox_hash::XxHash64::oneshot(0, data))
+}
+
+fn symbol_index_key(symbol: &str) -> String {
+    format!("{symbol}/{INDEX_FILE}")
+}
+
+/// Write the root and per-symbol index files that [`HttpBackend`] uses for
+/// listings. Run after populating a store that is published over HTTP.
+pub fn write_index(store: &CandleStore) -> Result<StoreIndex, MarketDataError> {
+    let backend = store.backend();
+    let index = StoreIndex {
+        symbols: store.list_symbols()?,
+    };
+
+    for symbol in &index.symbols {
+        let mut files = SymbolIndex::default();
+        for key in backend.list(symbol)? {
+            if !key.ends_with(".parquet") {
+                continue;
+            }
+            if let Some(data) = backend.get(&key)? {
+                files.files.insert(key.clone(), data.len() as u64);
+                files.hashes.insert(key, content_hash(&data));
+            }
+        }
+        backend.put(&symbol_index_key(symbol), to_json(&files)?)?;
+    }
+    backend.put(INDEX_FILE, to_json(&index)?)?;
+
+    Ok(index)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Bytes, MarketDataError> {
+    serde_json::to_vec_pretty(value)
+        .map(Bytes::from)
+        .map_err(|e| MarketDataError::InvalidData(format!("failed to encode index: {e}")))
+}
+
+fn from_json<T: for<'de> Deserialize<'de>>(key: &str, data: &[u8]) -> Result<T, MarketDataError> {
+    serde_json::from_slice(data)
+        .map_err(|e| MarketDataError::InvalidData(format!("invalid index {key}: {e}")))
+}
+
+/// Read-only [`StorageBackend`] over a static HTTP server hosting a data
+/// directory, with listings served from [`write_index`]'s index files.
+///
+/// Data files are fetched lazily and, with a cache directory, kept on disk;
+/// a cached file is reused while its content hash matches the symbol index.
+/// Keys missing from the index are reported absent without a request. Index
+/// files are fetched once per backend. Uses a blocking HTTP client, so calls
+/// must not be made from inside an async context.
+#[derive(Debug)]
+pub struct HttpBackend {
+    base_url: String,
+    client: reqwest::blocking::Client,
+    disk_cache: Option<LocalBackend>,
+    root_index: OnceLock<StoreIndex>,
+    symbol_indexes: RwLock<HashMap<String, Arc<SymbolIndex>>>,
+}
+
+impl HttpBackend {
+    /// `base_url` points at the data directory, e.g.
+    /// `https://example.com/market-data/data`.
+    pub fn new(base_url: &str) -> Result<Self, MarketDataError> {
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(concat!("market-data/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| MarketDataError::Storage(e.to_string()))?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+            disk_cache: None,
+            root_index: OnceLock::new(),
+            symbol_indexes: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Keep downloaded day files under `dir`, in the store's layout.
+    pub fn with_cache_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.disk_cache = Some(LocalBackend::new(dir));
+        self
+    }
+
+    /// GET a key, mapping 404 to `None`.
+    fn fetch(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+        let url = format!("{}/{key}", self.base_url);
+        let storage_error = |e: reqwest::Error| MarketDataError::Storage(format!("GET {url}: {e}"));
+
+        let response = self.client.get(&url).send().map_err(storage_error)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status().map_err(storage_error)?;
+        response.bytes().map(Some).map_err(storage_error)
+    }
+
+    fn root_index(&self) -> Result<&StoreIndex, MarketDataError> {
+        if let Some(index) = self.root_index.get() {
+            return Ok(index);
+        }
+        let index = match self.fetch(INDEX_FILE)? {
+            Some(data) => from_json(INDEX_FILE, &data)?,
+            None => StoreIndex::default(),
+        };
+        Ok(self.root_index.get_or_init(|| index))
+    }
+
+    fn symbol_index(&self, symbol: &str) -> Result<Arc<SymbolIndex>, MarketDataError> {
+        if let Some(index) = self
+            .symbol_indexes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(symbol)
+        {
+            return Ok(index.clone());
+        }
+
+        let key = symbol_index_key(symbol);
+        let index = Arc::new(match self.fetch(&key)? {
+            Some(data) => from_json(&key, &data)?,
+            None => SymbolIndex::default(),
+        });
+        self.symbol_indexes
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(symbol.to_string(), index.clone());
+        Ok(index)
+    }
+
+    /// Indexed keys under a prefix; the first path segment names the symbol.
+    fn indexed_keys(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        let prefix = prefix.trim_matches('/');
+        let symbols = match prefix.split_once('/') {
+            _ if prefix.is_empty() => self.root_index()?.symbols.clone(),
+            Some((symbol, _)) => vec![symbol.to_string()],
+            None => vec![prefix.to_string()],
+        };
+
+        let dir = format!("{prefix}/");
+        let mut keys = Vec::new();
+        for symbol in symbols {
+            keys.extend(
+                self.symbol_index(&symbol)?
+                    .files
+                    .keys()
+                    .filter(|key| prefix.is_empty() || key.starts_with(&dir))
+                    .cloned(),
+            );
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn read_only(&self) -> MarketDataError {
+        MarketDataError::Storage(format!("{} is a read-only HTTP store", self.base_url))
+    }
+}
+
+impl StorageBackend for HttpBackend {
+    fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+        // Config and index files aren't listed in the symbol index
+        let Some((symbol, _)) = key.split_once('/').filter(|_| key.ends_with(".parquet")) else {
+            return self.fetch(key);
+        };
+        let index = self.symbol_index(symbol)?;
+        if !index.files.contains_key(key) {
+            return Ok(None);
+        }
+
+        let Some(cache) = &self.disk_cache else {
+            return self.fetch(key);
+        };
+        if let Some(hash) = index.hashes.get(key)
+            && let Some(cached) = cache.get(key)?
+            && content_hash(&cached) == *hash
+        {
+            return Ok(Some(cached));
+        }
+
+        let data = self.fetch(key)?;
+        if let Some(data) = &data {
+            cache.put(key, data.clone())?;
+        }
+        Ok(data)
+    }
+
+    fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+        let Some((symbol, _)) = key.split_once('/') else {
+            return Ok(None);
+        };
+        Ok(self
+            .symbol_index(symbol)?
+            .files
+            .get(key)
+            .map(|&size| ObjectMeta {
+                size,
+                last_modified: None,
+            }))
+    }
+
+    fn put(&self, _key: &str, _data: Bytes) -> Result<(), MarketDataError> {
+        Err(self.read_only())
+    }
+
+    fn delete(&self, _key: &str) -> Result<(), MarketDataError> {
+        Err(self.read_only())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        self.indexed_keys(prefix)
+    }
+
+    fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+        let prefix = prefix.trim_matches('/');
+        if prefix.is_empty() {
+            return Ok(self.root_index()?.symbols.clone());
+        }
+
+        let dir = format!("{prefix}/");
+        let mut names: Vec<String> = self
+            .indexed_keys(prefix)?
+            .iter()
+            .filter_map(|key| key[dir.len()..].split_once('/'))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+/// Read-only [`CandleStore`] over a dataset published on a static HTTP
+/// server (e.g. a raw git hosting URL), caching day files on disk.
+///
+/// Dereferences to [`CandleStore`], so the whole read and query API is
+/// available; writes fail with [`MarketDataError::Storage`]. Enabled with the
+/// `http` feature.
+#[derive(Debug, Clone)]
+pub struct RemoteCandleStore {
+    store: CandleStore,
+}
+
+impl RemoteCandleStore {
+    /// Read from `{root_url}/data/`, caching day files under `cache_dir`.
+    pub fn new(root_url: &str, cache_dir: impl AsRef<Path>) -> Result<Self, MarketDataError> {
+        let backend = HttpBackend::new(&format!("{}/data", root_url.trim_end_matches('/')))?
+            .with_cache_dir(cache_dir);
+        Ok(Self::from_backend(backend))
+    }
+
+    pub fn from_backend(backend: HttpBackend) -> Self {
+        Self {
+            store: CandleStore::with_backend(Arc::new(backend)),
+        }
+    }
+
+    /// Enable the in-process cache of decoded days, on top of the disk cache.
+    pub fn with_cache(self, max_bytes: usize) -> Self {
+        Self {
+            store: self.store.with_cache(max_bytes),
+        }
+    }
+
+    pub fn into_store(self) -> CandleStore {
+        self.store
+    }
+}
+
+impl Deref for RemoteCandleStore {
+    type Target = CandleStore;
+
+    fn deref(&self) -> &CandleStore {
+        &self.store
+    }
+}
+
+impl CandleSource for RemoteCandleStore {
+    fn read_day(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Candle>, MarketDataError> {
+        self.store.read_day(symbol, date)
+    }
+
+    fn list_symbols(&self) -> Result<Vec<String>, MarketDataError> {
+        self.store.list_symbols()
+    }
+
+    fn list_dates(&self, symbol: &str) -> Result<Vec<NaiveDate>, MarketDataError> {
+        self.store.list_dates(symbol)
+    }
+
+    fn read_range(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        self.store.read_range(symbol, start, end)
+    }
+
+    fn date_range(&self, symbol: &str) -> Result<Option<(NaiveDate, NaiveDate)>, MarketDataError> {
+        self.store.date_range(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, d).unwrap()
+    }
+
+    fn candles(day: u32) -> Vec<Candle> {
+        [(14, 30), (22, 0)]
+            .into_iter()
+            .map(|(hour, min)| Candle {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, day, hour, min, 0).unwrap(),
+                open: dec!(150.00),
+                high: dec!(151.00),
+                low: dec!(149.00),
+                close: dec!(150.50),
+                volume: 1000,
+                vwap: None,
+                trade_count: None,
+            })
+            .collect()
+    }
+
+    /// Minimal static file server over `root`; returns its base URL and a
+    /// count of requests for Parquet files.
+    fn serve(root: PathBuf) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let parquet_requests = Arc::new(AtomicUsize::new(0));
+        let counter = parquet_requests.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&stream);
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                // Drain headers
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    line.clear();
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                if path.ends_with(".parquet") {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+                let file = path
+                    .trim_start_matches('/')
+                    .split('/')
+                    .fold(root.clone(), |p, part| p.join(part));
+                let response = match std::fs::read(&file) {
+                    Ok(body) => {
+                        let mut r = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        r.extend(body);
+                        r
+                    }
+                    Err(_) => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        (url, parquet_requests)
+    }
+
+    fn published_store() -> (tempfile::TempDir, String, Arc<AtomicUsize>) {
+        let root = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(root.path());
+        for day in [14, 15, 16] {
+            store.write_day("AAPL", date(day), &candles(day)).unwrap();
+        }
+        store.write_day("MSFT", date(15), &candles(15)).unwrap();
+        write_index(&store).unwrap();
+
+        let (url, requests) = serve(root.path().to_path_buf());
+        (root, url, requests)
+    }
+
+    #[test]
+    fn write_index_lists_symbols_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        store.write_day("AAPL", date(15), &candles(15)).unwrap();
+
+        let index = write_index(&store).unwrap();
+        assert_eq!(index.symbols, vec!["AAPL"]);
+
+        let data = store.backend().get("AAPL/index.json").unwrap().unwrap();
+        let symbol_index: SymbolIndex = from_json("AAPL/index.json", &data).unwrap();
+        let key = CandleStore::key("AAPL", date(15));
+        let data = store.backend().get(&key).unwrap().unwrap();
+        assert_eq!(symbol_index.hashes.get(&key), Some(&content_hash(&data)));
+        assert_eq!(
+            symbol_index.files.get(&key).copied(),
+            Some(
+                std::fs::metadata(store.file_path("AAPL", date(15)))
+                    .unwrap()
+                    .len()
+            )
+        );
+
+        // Index files don't show up as dates
+        assert_eq!(store.list_dates("AAPL").unwrap(), vec![date(15)]);
+    }
+
+    #[test]
+    fn reads_and_lists_over_http() {
+        let (_root, url, _) = published_store();
+        let cache = tempfile::tempdir().unwrap();
+        let remote = RemoteCandleStore::new(&url, cache.path()).unwrap();
+
+        assert_eq!(remote.list_symbols().unwrap(), vec!["AAPL", "MSFT"]);
+        assert_eq!(
+            remote.date_range("AAPL").unwrap(),
+            Some((date(14), date(16)))
+        );
+        assert_eq!(remote.read_day("AAPL", date(15)).unwrap(), candles(15));
+        assert_eq!(
+            remote
+                .read_range_session("AAPL", date(14), date(16), Session::Regular)
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(matches!(
+            remote.read_day("AAPL", date(17)),
+            Err(MarketDataError::NoData { .. })
+        ));
+        assert!(remote.list_dates("TSLA").unwrap().is_empty());
+        assert!(matches!(
+            remote.write_day("AAPL", date(17), &candles(17)),
+            Err(MarketDataError::Storage(_))
+        ));
+    }
+
+    #[test]
+    fn disk_cache_avoids_refetching() {
+        let (_root, url, requests) = published_store();
+        let cache = tempfile::tempdir().unwrap();
+
+        let remote = RemoteCandleStore::new(&url, cache.path()).unwrap();
+        remote.read_range("AAPL", date(14), date(16)).unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(
+            cache
+                .path()
+                .join("AAPL/2025/01/2025-01-15.parquet")
+                .is_file()
+        );
+
+        // A fresh store over the same cache directory reads from disk
+        let remote = RemoteCandleStore::new(&url, cache.path()).unwrap();
+        assert_eq!(
+            remote.read_range("AAPL", date(14), date(16)).unwrap().len(),
+            6
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // A cached file that no longer matches the index is fetched again,
+        // even at the same size
+        let cached = cache.path().join("AAPL/2025/01/2025-01-15.parquet");
+        let mut data = std::fs::read(&cached).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        std::fs::write(&cached, data).unwrap();
+        let remote = RemoteCandleStore::new(&url, cache.path()).unwrap();
+        assert_eq!(remote.read_day("AAPL", date(15)).unwrap(), candles(15));
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn unindexed_files_are_absent_without_a_request() {
+        let (_root, url, requests) = published_store();
+        let cache = tempfile::tempdir().unwrap();
+        let remote = RemoteCandleStore::new(&url, cache.path()).unwrap();
+
+        assert!(!remote.has_data("AAPL", date(17)));
+        assert!(matches!(
+            remote.read_day("AAPL", date(17)),
+            Err(MarketDataError::NoData { .. })
+        ));
+        assert_eq!(
+            remote.read_range("AAPL", date(13), date(17)).unwrap().len(),
+            6
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1,17 +1,57 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use arrow::array::{ArrayRef, Int64Array, StringArray, TimestampMicrosecondArray};
-use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, Int64Array, StringArray, TimestampMicrosecondArray,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Decimal128Type, Field, Float64Type, Int64Type, Schema, TimeUnit};
+use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
-use parquet::arrow::ArrowWriter;
-use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use parquet::basic::Compression;
-use parquet::file::properties::WriterProperties;
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDate, Utc};
+use parquet::arrow::arrow_reader::{
+    ArrowPredicateFn, ArrowReaderOptions, ParquetRecordBatchReaderBuilder, RowFilter, RowSelection,
+    RowSelector,
+};
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::file::metadata::{KeyValue, ParquetMetaData, SortingColumn};
+use parquet::file::page_index::column_index::ColumnIndexMetaData;
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::SchemaDescriptor;
+use rust_decimal::Decimal;
 
-use crate::candle::Candle;
+use crate::candle::{Candle, Timeframe};
+use crate::config::StoreConfig;
 use crate::error::MarketDataError;
 
+/// Version of the on-disk candle schema, stored in each file's key-value metadata.
+///
+/// Version 2 added the nullable `vwap` and `trade_count` columns.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Key-value metadata key holding [`SCHEMA_VERSION`].
+pub const SCHEMA_VERSION_KEY: &str = "market_data.schema_version";
+
+/// Key-value metadata key listing the UTC dates held by a multi-day pack file,
+/// comma-separated `YYYY-MM-DD`.
+pub const PACK_DATES_KEY: &str = "market_data.pack_dates";
+
+/// Key-value metadata key marking a pack file of daily bars, with value
+/// `daily`. Files without it hold five-minute bars.
+pub const TIMEFRAME_KEY: &str = "market_data.timeframe";
+
+/// Summary of a Parquet candle file, read from its footer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStats {
+    pub row_count: i64,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    /// `None` for files written before the schema version was recorded.
+    pub schema_version: Option<u32>,
+}
+
 pub fn candle_schema() -> Schema {
     Schema::new(vec![
         Field::new(
@@ -24,6 +64,8 @@ pub fn candle_schema() -> Schema {
         Field::new("low", DataType::Utf8, false),
         Field::new("close", DataType::Utf8, false),
         Field::new("volume", DataType::Int64, false),
+        Field::new("vwap", DataType::Utf8, true),
+        Field::new("trade_count", DataType::Int64, true),
     ])
 }
 
@@ -40,6 +82,11 @@ pub fn candles_to_record_batch(candles: &[Candle]) -> Result<RecordBatch, Market
     let lows: Vec<String> = candles.iter().map(|c| c.low.to_string()).collect();
     let closes: Vec<String> = candles.iter().map(|c| c.close.to_string()).collect();
     let volumes: Vec<i64> = candles.iter().map(|c| c.volume).collect();
+    let vwaps: Vec<Option<String>> = candles
+        .iter()
+        .map(|c| c.vwap.map(|v| v.to_string()))
+        .collect();
+    let trade_counts: Vec<Option<i64>> = candles.iter().map(|c| c.trade_count).collect();
 
     let columns: Vec<ArrayRef> = vec![
         Arc::new(TimestampMicrosecondArray::from(timestamps).with_timezone("UTC")),
@@ -56,103 +103,329 @@ pub fn candles_to_record_batch(candles: &[Candle]) -> Result<RecordBatch, Market
             closes.iter().map(|s| s.as_ref()).collect::<Vec<&str>>(),
         )),
         Arc::new(Int64Array::from(volumes)),
+        Arc::new(StringArray::from(vwaps)),
+        Arc::new(Int64Array::from(trade_counts)),
     ];
 
     Ok(RecordBatch::try_new(schema, columns)?)
 }
 
+/// Convert a record batch to candles, resolving columns by name.
+///
+/// Besides the native schema this accepts reordered or extra columns,
+/// `timestamp` in any [`TimeUnit`] (nanoseconds are truncated to
+/// microseconds), prices as Float32/Float64, Decimal128 or integers, and
+/// volume as any integer type. The optional `vwap` and `trade_count` columns
+/// read as `None` when absent or null. Errors name the offending column.
 pub fn record_batch_to_candles(batch: &RecordBatch) -> Result<Vec<Candle>, MarketDataError> {
-    let timestamps = batch
-        .column(0)
-        .as_any()
-        .downcast_ref::<TimestampMicrosecondArray>()
-        .ok_or_else(|| MarketDataError::InvalidData("expected timestamp column".into()))?;
-
-    let opens = batch
-        .column(1)
-        .as_any()
-        .downcast_ref::<StringArray>()
-        .ok_or_else(|| MarketDataError::InvalidData("expected open column".into()))?;
-
-    let highs = batch
-        .column(2)
-        .as_any()
-        .downcast_ref::<StringArray>()
-        .ok_or_else(|| MarketDataError::InvalidData("expected high column".into()))?;
-
-    let lows = batch
-        .column(3)
-        .as_any()
-        .downcast_ref::<StringArray>()
-        .ok_or_else(|| MarketDataError::InvalidData("expected low column".into()))?;
-
-    let closes = batch
-        .column(4)
-        .as_any()
-        .downcast_ref::<StringArray>()
-        .ok_or_else(|| MarketDataError::InvalidData("expected close column".into()))?;
-
-    let volumes = batch
-        .column(5)
-        .as_any()
-        .downcast_ref::<Int64Array>()
-        .ok_or_else(|| MarketDataError::InvalidData("expected volume column".into()))?;
+    let timestamps = timestamp_micros(column(batch, "timestamp")?)?;
+    let opens = decimal_values(batch, "open")?;
+    let highs = decimal_values(batch, "high")?;
+    let lows = decimal_values(batch, "low")?;
+    let closes = decimal_values(batch, "close")?;
+    let volumes = volume_values(batch)?;
+    let vwaps = match batch.column_by_name("vwap") {
+        Some(array) => nullable_decimal_values(array, "vwap")?,
+        None => vec![None; batch.num_rows()],
+    };
+    let trade_counts = match batch.column_by_name("trade_count") {
+        Some(array) => nullable_int_values(array, "trade_count")?,
+        None => vec![None; batch.num_rows()],
+    };
 
     let mut candles = Vec::with_capacity(batch.num_rows());
     for i in 0..batch.num_rows() {
+        if timestamps.is_null(i) {
+            return Err(column_error("timestamp", format!("null at row {i}")));
+        }
         let micros = timestamps.value(i);
-        let timestamp = chrono::DateTime::from_timestamp_micros(micros)
-            .ok_or_else(|| MarketDataError::InvalidData(format!("invalid timestamp: {micros}")))?;
-
-        let open = opens
-            .value(i)
-            .parse()
-            .map_err(|e| MarketDataError::InvalidData(format!("invalid open: {e}")))?;
-        let high = highs
-            .value(i)
-            .parse()
-            .map_err(|e| MarketDataError::InvalidData(format!("invalid high: {e}")))?;
-        let low = lows
-            .value(i)
-            .parse()
-            .map_err(|e| MarketDataError::InvalidData(format!("invalid low: {e}")))?;
-        let close = closes
-            .value(i)
-            .parse()
-            .map_err(|e| MarketDataError::InvalidData(format!("invalid close: {e}")))?;
-        let volume = volumes.value(i);
+        let timestamp = chrono::DateTime::from_timestamp_micros(micros).ok_or_else(|| {
+            column_error(
+                "timestamp",
+                format!("invalid timestamp at row {i}: {micros}"),
+            )
+        })?;
 
         candles.push(Candle {
             timestamp,
-            open,
-            high,
-            low,
-            close,
-            volume,
+            open: opens[i],
+            high: highs[i],
+            low: lows[i],
+            close: closes[i],
+            volume: volumes[i],
+            vwap: vwaps[i],
+            trade_count: trade_counts[i],
         });
     }
 
     Ok(candles)
 }
 
-pub fn write_parquet(path: &Path, candles: &[Candle]) -> Result<(), MarketDataError> {
-    let batch = candles_to_record_batch(candles)?;
+fn column_error(name: &str, message: impl std::fmt::Display) -> MarketDataError {
+    MarketDataError::InvalidData(format!("column `{name}`: {message}"))
+}
 
-    let props = WriterProperties::builder()
-        .set_compression(Compression::SNAPPY)
-        .build();
+fn column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef, MarketDataError> {
+    batch
+        .column_by_name(name)
+        .ok_or_else(|| MarketDataError::InvalidData(format!("missing column `{name}`")))
+}
+
+fn cast_column(array: &ArrayRef, name: &str, to: &DataType) -> Result<ArrayRef, MarketDataError> {
+    cast(array, to).map_err(|e| column_error(name, e))
+}
 
-    let file = std::fs::File::create(path)?;
-    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+/// Timestamps of any unit as microseconds since the epoch.
+fn timestamp_micros(array: &ArrayRef) -> Result<TimestampMicrosecondArray, MarketDataError> {
+    let DataType::Timestamp(unit, _) = array.data_type() else {
+        return Err(column_error(
+            "timestamp",
+            format!("unsupported type {}", array.data_type()),
+        ));
+    };
+    let micros = if *unit == TimeUnit::Microsecond {
+        array.clone()
+    } else {
+        cast_column(
+            array,
+            "timestamp",
+            &DataType::Timestamp(TimeUnit::Microsecond, None),
+        )?
+    };
+    micros
+        .as_any()
+        .downcast_ref::<TimestampMicrosecondArray>()
+        .cloned()
+        .ok_or_else(|| column_error("timestamp", "expected microsecond timestamps"))
+}
+
+fn decimal_values(batch: &RecordBatch, name: &str) -> Result<Vec<Decimal>, MarketDataError> {
+    required(nullable_decimal_values(column(batch, name)?, name)?, name)
+}
+
+fn nullable_decimal_values(
+    array: &ArrayRef,
+    name: &str,
+) -> Result<Vec<Option<Decimal>>, MarketDataError> {
+    match array.data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            let strings = cast_column(array, name, &DataType::Utf8)?;
+            strings
+                .as_string::<i32>()
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    s.map(|s| {
+                        s.parse().map_err(|e| {
+                            column_error(name, format!("invalid value {s:?} at row {i}: {e}"))
+                        })
+                    })
+                    .transpose()
+                })
+                .collect()
+        }
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            let floats = cast_column(array, name, &DataType::Float64)?;
+            floats
+                .as_primitive::<Float64Type>()
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    f.map(|f| {
+                        Decimal::try_from(f).map_err(|e| {
+                            column_error(name, format!("invalid value {f} at row {i}: {e}"))
+                        })
+                    })
+                    .transpose()
+                })
+                .collect()
+        }
+        &DataType::Decimal128(_, scale) => {
+            let scale = u32::try_from(scale)
+                .map_err(|_| column_error(name, format!("unsupported negative scale {scale}")))?;
+            array
+                .as_primitive::<Decimal128Type>()
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    v.map(|v| {
+                        Decimal::try_from_i128_with_scale(v, scale).map_err(|e| {
+                            column_error(name, format!("invalid value at row {i}: {e}"))
+                        })
+                    })
+                    .transpose()
+                })
+                .collect()
+        }
+        t if t.is_integer() => Ok(nullable_int_values(array, name)?
+            .into_iter()
+            .map(|v| v.map(Decimal::from))
+            .collect()),
+        other => Err(column_error(name, format!("unsupported type {other}"))),
+    }
+}
+
+fn volume_values(batch: &RecordBatch) -> Result<Vec<i64>, MarketDataError> {
+    required(
+        nullable_int_values(column(batch, "volume")?, "volume")?,
+        "volume",
+    )
+}
+
+/// Integers of any width as `i64`, keeping nulls.
+fn nullable_int_values(array: &ArrayRef, name: &str) -> Result<Vec<Option<i64>>, MarketDataError> {
+    if !array.data_type().is_integer() {
+        return Err(column_error(
+            name,
+            format!("unsupported type {}", array.data_type()),
+        ));
+    }
+    let ints = cast_column(array, name, &DataType::Int64)?;
+    // Casting turns values that don't fit into nulls
+    if ints.null_count() > array.null_count() {
+        let row = (0..ints.len())
+            .find(|&i| ints.is_null(i) && !array.is_null(i))
+            .unwrap_or(0);
+        return Err(column_error(
+            name,
+            format!("out of range for Int64 at row {row}"),
+        ));
+    }
+    Ok(ints.as_primitive::<Int64Type>().iter().collect())
+}
+
+/// Values of a non-nullable column, or an error naming the first null row.
+fn required<T>(values: Vec<Option<T>>, name: &str) -> Result<Vec<T>, MarketDataError> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| v.ok_or_else(|| column_error(name, format!("null at row {i}"))))
+        .collect()
+}
+
+/// Leaf index of the `timestamp` column and whether it is stored in
+/// microseconds, the unit its statistics are compared in.
+fn timestamp_leaf(
+    parquet_schema: &SchemaDescriptor,
+    arrow_schema: &Schema,
+) -> Result<(usize, bool), MarketDataError> {
+    let leaf = parquet_schema
+        .columns()
+        .iter()
+        .position(|c| c.path().parts() == ["timestamp"])
+        .ok_or_else(|| MarketDataError::InvalidData("missing column `timestamp`".into()))?;
+    let micros = matches!(
+        arrow_schema
+            .field_with_name("timestamp")
+            .map(|f| f.data_type()),
+        Ok(DataType::Timestamp(TimeUnit::Microsecond, _))
+    );
+    Ok((leaf, micros))
+}
+
+/// Writer settings for a file. Day files get pages of `config.page_rows`
+/// rows, which together with the page index lets time-window reads skip most
+/// of a file; packs keep Parquet's byte-sized pages.
+fn writer_properties(
+    config: &StoreConfig,
+    metadata: Vec<KeyValue>,
+    row_pages: bool,
+) -> Result<WriterProperties, MarketDataError> {
+    config.validate()?;
+    let mut builder = WriterProperties::builder()
+        .set_compression(config.compression()?)
+        .set_dictionary_enabled(config.dictionary)
+        .set_max_row_group_size(config.max_row_group_size)
+        .set_statistics_enabled(config.statistics.into())
+        .set_column_statistics_enabled("timestamp".into(), EnabledStatistics::Page)
+        .set_sorting_columns(Some(vec![SortingColumn {
+            column_idx: 0,
+            descending: false,
+            nulls_first: false,
+        }]))
+        .set_key_value_metadata(Some(
+            std::iter::once(KeyValue::new(
+                SCHEMA_VERSION_KEY.to_string(),
+                SCHEMA_VERSION.to_string(),
+            ))
+            .chain(metadata)
+            .collect(),
+        ));
+    if row_pages {
+        builder = builder
+            .set_data_page_row_count_limit(config.page_rows)
+            .set_write_batch_size(config.page_rows);
+    }
+    if let Some(fpp) = config.bloom_filter_fpp {
+        builder = builder
+            .set_bloom_filter_enabled(true)
+            .set_bloom_filter_fpp(fpp);
+    }
+    Ok(builder.build())
+}
+
+/// Encode candles as an in-memory Parquet file with default settings.
+pub fn encode_parquet(candles: &[Candle]) -> Result<Vec<u8>, MarketDataError> {
+    encode_parquet_with(candles, &StoreConfig::default())
+}
+
+/// Encode candles as an in-memory Parquet file with the given writer settings.
+pub fn encode_parquet_with(
+    candles: &[Candle],
+    config: &StoreConfig,
+) -> Result<Vec<u8>, MarketDataError> {
+    encode(candles, config, Vec::new(), true)
+}
+
+/// Encode candles spanning several days as a pack file, recording the UTC
+/// dates it holds under [`PACK_DATES_KEY`] and, for daily bars,
+/// [`TIMEFRAME_KEY`]. Candles must be sorted by timestamp.
+pub fn encode_pack_with(
+    candles: &[Candle],
+    config: &StoreConfig,
+    timeframe: Timeframe,
+) -> Result<Vec<u8>, MarketDataError> {
+    let mut dates: Vec<NaiveDate> = candles.iter().map(|c| c.timestamp.date_naive()).collect();
+    dates.dedup();
+    let dates: Vec<String> = dates.iter().map(|d| d.to_string()).collect();
+    let mut metadata = vec![KeyValue::new(PACK_DATES_KEY.to_string(), dates.join(","))];
+    if timeframe == Timeframe::Daily {
+        metadata.push(KeyValue::new(
+            TIMEFRAME_KEY.to_string(),
+            "daily".to_string(),
+        ));
+    }
+    encode(candles, config, metadata, false)
+}
+
+fn encode(
+    candles: &[Candle],
+    config: &StoreConfig,
+    metadata: Vec<KeyValue>,
+    row_pages: bool,
+) -> Result<Vec<u8>, MarketDataError> {
+    let batch = candles_to_record_batch(candles)?;
+
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(
+        &mut buffer,
+        batch.schema(),
+        Some(writer_properties(config, metadata, row_pages)?),
+    )?;
     writer.write(&batch)?;
     writer.close()?;
 
+    Ok(buffer)
+}
+
+pub fn write_parquet(path: &Path, candles: &[Candle]) -> Result<(), MarketDataError> {
+    std::fs::write(path, encode_parquet(candles)?)?;
     Ok(())
 }
 
-pub fn read_parquet(path: &Path) -> Result<Vec<Candle>, MarketDataError> {
-    let file = std::fs::File::open(path)?;
-    let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+/// Decode all candles from an in-memory Parquet file.
+pub fn decode_parquet(data: Bytes) -> Result<Vec<Candle>, MarketDataError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data)?;
     let reader = builder.build()?;
 
     let mut all_candles = Vec::new();
@@ -165,6 +438,291 @@ pub fn read_parquet(path: &Path) -> Result<Vec<Candle>, MarketDataError> {
     Ok(all_candles)
 }
 
+pub fn read_parquet(path: &Path) -> Result<Vec<Candle>, MarketDataError> {
+    decode_parquet(Bytes::from(std::fs::read(path)?)).map_err(|e| e.in_file(path.display()))
+}
+
+/// Read only the candles whose timestamp satisfies `predicate`.
+///
+/// `ranges` are inclusive UTC windows that must cover every matching candle.
+/// Row groups and pages whose timestamp statistics fall outside all of them
+/// are skipped without decoding, and within the remaining pages prices are
+/// decoded only for rows that pass `predicate`.
+pub fn decode_parquet_filtered<F>(
+    data: Bytes,
+    ranges: &[(DateTime<Utc>, DateTime<Utc>)],
+    predicate: F,
+) -> Result<Vec<Candle>, MarketDataError>
+where
+    F: Fn(&DateTime<Utc>) -> bool + Send + 'static,
+{
+    let ranges: Vec<(i64, i64)> = ranges
+        .iter()
+        .map(|(lo, hi)| (lo.timestamp_micros(), hi.timestamp_micros()))
+        .collect();
+    let overlaps = |min: i64, max: i64| ranges.iter().any(|&(lo, hi)| min <= hi && max >= lo);
+
+    let options = ArrowReaderOptions::new().with_page_index(true);
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(data, options)?;
+    let metadata = builder.metadata().clone();
+    // Statistics are only comparable with the ranges for microsecond files
+    let (leaf, prune) = timestamp_leaf(builder.parquet_schema(), builder.schema())?;
+
+    let mut row_groups = Vec::new();
+    let mut selectors = Vec::new();
+    for (i, row_group) in metadata.row_groups().iter().enumerate() {
+        if !prune {
+            row_groups.push(i);
+            selectors.push(RowSelector::select(row_group.num_rows() as usize));
+            continue;
+        }
+        if let Some(Statistics::Int64(stats)) = row_group.column(leaf).statistics()
+            && let (Some(&min), Some(&max)) = (stats.min_opt(), stats.max_opt())
+            && !overlaps(min, max)
+        {
+            continue;
+        }
+        row_groups.push(i);
+        selectors.extend(page_selectors(&metadata, i, leaf, overlaps));
+    }
+
+    if row_groups.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mask = ProjectionMask::leaves(builder.parquet_schema(), [leaf]);
+    let row_filter = ArrowPredicateFn::new(mask, move |batch: RecordBatch| {
+        let timestamps = batch
+            .column_by_name("timestamp")
+            .ok_or_else(|| ArrowError::SchemaError("missing column `timestamp`".into()))
+            .and_then(|array| {
+                timestamp_micros(array).map_err(|e| ArrowError::SchemaError(e.to_string()))
+            })?;
+        Ok(timestamps
+            .iter()
+            .map(|micros| {
+                Some(
+                    micros
+                        .and_then(DateTime::from_timestamp_micros)
+                        .is_some_and(|ts| predicate(&ts)),
+                )
+            })
+            .collect::<BooleanArray>())
+    });
+
+    let reader = builder
+        .with_row_groups(row_groups)
+        .with_row_selection(RowSelection::from(selectors))
+        .with_row_filter(RowFilter::new(vec![Box::new(row_filter)]))
+        .build()?;
+
+    let mut all_candles = Vec::new();
+    for batch in reader {
+        let batch = batch?;
+        let mut candles = record_batch_to_candles(&batch)?;
+        all_candles.append(&mut candles);
+    }
+
+    Ok(all_candles)
+}
+
+/// Row selectors for one row group, skipping pages whose timestamp range
+/// does not overlap. Selects the whole row group if there is no page index.
+fn page_selectors(
+    metadata: &ParquetMetaData,
+    row_group: usize,
+    leaf: usize,
+    overlaps: impl Fn(i64, i64) -> bool,
+) -> Vec<RowSelector> {
+    let num_rows = metadata.row_group(row_group).num_rows() as usize;
+    let select_all = vec![RowSelector::select(num_rows)];
+
+    let (Some(column_index), Some(offset_index)) =
+        (metadata.column_index(), metadata.offset_index())
+    else {
+        return select_all;
+    };
+    let ColumnIndexMetaData::INT64(index) = &column_index[row_group][leaf] else {
+        return select_all;
+    };
+
+    let pages = offset_index[row_group][leaf].page_locations();
+    pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| {
+            let first_row = page.first_row_index as usize;
+            let end_row = pages
+                .get(i + 1)
+                .map_or(num_rows, |next| next.first_row_index as usize);
+            let keep = match (index.min_value(i), index.max_value(i)) {
+                (Some(&min), Some(&max)) => overlaps(min, max),
+                _ => true,
+            };
+            if keep {
+                RowSelector::select(end_row - first_row)
+            } else {
+                RowSelector::skip(end_row - first_row)
+            }
+        })
+        .collect()
+}
+
+/// Read row count, timestamp range and schema version from a file's footer
+/// without decoding prices. Falls back to decoding only the timestamp column
+/// for files written without column statistics.
+pub fn read_parquet_stats(path: &Path) -> Result<FileStats, MarketDataError> {
+    decode_parquet_stats(Bytes::from(std::fs::read(path)?)).map_err(|e| e.in_file(path.display()))
+}
+
+/// [`read_parquet_stats`] for an in-memory Parquet file.
+pub fn decode_parquet_stats(data: Bytes) -> Result<FileStats, MarketDataError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data.clone())?;
+    let metadata = builder.metadata();
+    let file_metadata = metadata.file_metadata();
+    let (leaf, micros) = timestamp_leaf(builder.parquet_schema(), builder.schema())?;
+
+    let schema_version = file_metadata
+        .key_value_metadata()
+        .and_then(|kv| kv.iter().find(|kv| kv.key == SCHEMA_VERSION_KEY))
+        .and_then(|kv| kv.value.as_deref())
+        .and_then(|v| v.parse().ok());
+
+    let mut min: Option<i64> = None;
+    let mut max: Option<i64> = None;
+    let mut complete = micros;
+    for row_group in metadata.row_groups() {
+        if row_group.num_rows() == 0 || !complete {
+            continue;
+        }
+        match row_group
+            .column(leaf)
+            .statistics()
+            .map(int64_bounds)
+            .transpose()?
+        {
+            Some(Some((lo, hi))) => {
+                min = Some(min.map_or(lo, |m| m.min(lo)));
+                max = Some(max.map_or(hi, |m| m.max(hi)));
+            }
+            _ => complete = false,
+        }
+    }
+
+    if !complete {
+        (min, max) = timestamp_bounds(data)?;
+    }
+
+    let to_datetime = |micros: i64| {
+        DateTime::from_timestamp_micros(micros)
+            .ok_or_else(|| MarketDataError::InvalidData(format!("invalid timestamp: {micros}")))
+    };
+
+    Ok(FileStats {
+        row_count: file_metadata.num_rows(),
+        first_timestamp: min.map(to_datetime).transpose()?,
+        last_timestamp: max.map(to_datetime).transpose()?,
+        schema_version,
+    })
+}
+
+/// Min and max of a row group's Int64 column statistics, or `None` if it has
+/// none. A min without a max (or the reverse) means a corrupt footer.
+fn int64_bounds(stats: &Statistics) -> Result<Option<(i64, i64)>, MarketDataError> {
+    let Statistics::Int64(stats) = stats else {
+        return Ok(None);
+    };
+    match (stats.min_opt(), stats.max_opt()) {
+        (Some(&lo), Some(&hi)) => Ok(Some((lo, hi))),
+        (None, None) => Ok(None),
+        _ => Err(MarketDataError::InvalidData(
+            "timestamp statistics have only one of min and max".into(),
+        )),
+    }
+}
+
+/// Timeframe of the bars in a file, read from its footer: [`Timeframe::Daily`]
+/// for packs marked with [`TIMEFRAME_KEY`], five-minute bars otherwise.
+pub fn decode_timeframe(data: Bytes) -> Result<Timeframe, MarketDataError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data)?;
+    let value = builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|kv| kv.iter().find(|kv| kv.key == TIMEFRAME_KEY))
+        .and_then(|kv| kv.value.clone());
+    match value.as_deref() {
+        None => Ok(Timeframe::FiveMinute),
+        Some("daily") => Ok(Timeframe::Daily),
+        Some(other) => Err(MarketDataError::InvalidData(format!(
+            "unknown {TIMEFRAME_KEY} {other:?}"
+        ))),
+    }
+}
+
+/// UTC dates held by a pack file, read from its footer. Falls back to
+/// decoding the timestamp column for files without [`PACK_DATES_KEY`].
+pub fn decode_pack_dates(data: Bytes) -> Result<Vec<NaiveDate>, MarketDataError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data)?;
+    let listed = builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|kv| kv.iter().find(|kv| kv.key == PACK_DATES_KEY))
+        .and_then(|kv| kv.value.clone());
+    if let Some(listed) = listed {
+        return listed
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse().map_err(|e| {
+                    MarketDataError::InvalidData(format!(
+                        "invalid {PACK_DATES_KEY} entry {s:?}: {e}"
+                    ))
+                })
+            })
+            .collect();
+    }
+
+    let (leaf, _) = timestamp_leaf(builder.parquet_schema(), builder.schema())?;
+    let mask = ProjectionMask::leaves(builder.parquet_schema(), [leaf]);
+    let mut dates = Vec::new();
+    for batch in builder.with_projection(mask).build()? {
+        let batch = batch?;
+        let timestamps = timestamp_micros(column(&batch, "timestamp")?)?;
+        dates.extend(
+            timestamps
+                .iter()
+                .flatten()
+                .filter_map(DateTime::from_timestamp_micros)
+                .map(|ts| ts.date_naive()),
+        );
+    }
+    dates.sort();
+    dates.dedup();
+    Ok(dates)
+}
+
+/// Min and max of the timestamp column, decoding only that column.
+fn timestamp_bounds(data: Bytes) -> Result<(Option<i64>, Option<i64>), MarketDataError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data)?;
+    let (leaf, _) = timestamp_leaf(builder.parquet_schema(), builder.schema())?;
+    let mask = ProjectionMask::leaves(builder.parquet_schema(), [leaf]);
+    let reader = builder.with_projection(mask).build()?;
+
+    let mut min: Option<i64> = None;
+    let mut max: Option<i64> = None;
+    for batch in reader {
+        let batch = batch?;
+        let timestamps = timestamp_micros(column(&batch, "timestamp")?)?;
+        for micros in timestamps.iter().flatten() {
+            min = Some(min.map_or(micros, |m| m.min(micros)));
+            max = Some(max.map_or(micros, |m| m.max(micros)));
+        }
+    }
+    Ok((min, max))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +738,8 @@ mod tests {
                 low: dec!(149.0001),
                 close: dec!(150.9999),
                 volume: 1000,
+                vwap: None,
+                trade_count: None,
             },
             Candle {
                 timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 14, 35, 0).unwrap(),
@@ -188,6 +748,8 @@ mod tests {
                 low: dec!(150.50),
                 close: dec!(151.75),
                 volume: 2000,
+                vwap: None,
+                trade_count: None,
             },
         ]
     }
@@ -220,6 +782,181 @@ mod tests {
         assert_eq!(candles, result);
     }
 
+    /// A full day of 5-minute bars starting at 09:00 UTC.
+    fn full_day() -> Vec<Candle> {
+        (0..192)
+            .map(|i| Candle {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap()
+                    + chrono::Duration::minutes(5 * i),
+                open: dec!(150.00),
+                high: dec!(151.00),
+                low: dec!(149.00),
+                close: dec!(150.50),
+                volume: i,
+                vwap: None,
+                trade_count: None,
+            })
+            .collect()
+    }
+
+    fn hour_pages() -> StoreConfig {
+        StoreConfig {
+            page_rows: 12,
+            ..StoreConfig::default()
+        }
+    }
+
+    fn page_count(data: Vec<u8>) -> usize {
+        let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(
+            Bytes::from(data),
+            ArrowReaderOptions::new().with_page_index(true),
+        )
+        .unwrap();
+        assert!(builder.metadata().column_index().is_some());
+        builder.metadata().offset_index().unwrap()[0][0]
+            .page_locations()
+            .len()
+    }
+
+    #[test]
+    fn writes_page_index_with_configured_pages() {
+        let candles = full_day();
+        assert_eq!(
+            page_count(encode_parquet_with(&candles, &hour_pages()).unwrap()),
+            192 / 12
+        );
+        // Default pages hold a regular session: 78 + 78 + 36 rows
+        assert_eq!(page_count(encode_parquet(&candles).unwrap()), 3);
+        // Packs ignore the row limit
+        assert_eq!(
+            page_count(encode_pack_with(&candles, &hour_pages(), Timeframe::FiveMinute).unwrap()),
+            1
+        );
+    }
+
+    #[test]
+    fn filtered_read_matches_predicate() {
+        let candles = full_day();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day.parquet");
+        write_parquet(&path, &candles).unwrap();
+
+        let lo = Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap();
+        let hi = Utc.with_ymd_and_hms(2025, 1, 15, 15, 30, 0).unwrap();
+        let result = decode_parquet_filtered(
+            Bytes::from(std::fs::read(&path).unwrap()),
+            &[(lo, hi)],
+            move |ts| *ts >= lo && *ts <= hi,
+        )
+        .unwrap();
+
+        let expected: Vec<Candle> = candles
+            .into_iter()
+            .filter(|c| c.timestamp >= lo && c.timestamp <= hi)
+            .collect();
+        assert_eq!(result.len(), 7);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn filtered_read_prunes_pages() {
+        let data = encode_parquet_with(&full_day(), &hour_pages()).unwrap();
+
+        // With a pass-through predicate, only the page holding 15:00-15:55 is decoded
+        let lo = Utc.with_ymd_and_hms(2025, 1, 15, 15, 10, 0).unwrap();
+        let hi = Utc.with_ymd_and_hms(2025, 1, 15, 15, 20, 0).unwrap();
+        let result = decode_parquet_filtered(Bytes::from(data), &[(lo, hi)], |_| true).unwrap();
+        assert_eq!(result.len(), 12);
+        assert_eq!(
+            result[0].timestamp,
+            Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn filtered_read_outside_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("day.parquet");
+        write_parquet(&path, &full_day()).unwrap();
+
+        let lo = Utc.with_ymd_and_hms(2025, 1, 17, 0, 0, 0).unwrap();
+        let hi = Utc.with_ymd_and_hms(2025, 1, 17, 23, 0, 0).unwrap();
+        let result = decode_parquet_filtered(
+            Bytes::from(std::fs::read(&path).unwrap()),
+            &[(lo, hi)],
+            |_| true,
+        )
+        .unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn stats_from_footer() {
+        let candles = sample_candles();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stats.parquet");
+        write_parquet(&path, &candles).unwrap();
+
+        let stats = read_parquet_stats(&path).unwrap();
+        assert_eq!(stats.row_count, 2);
+        assert_eq!(stats.first_timestamp, Some(candles[0].timestamp));
+        assert_eq!(stats.last_timestamp, Some(candles[1].timestamp));
+        assert_eq!(stats.schema_version, Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn half_written_statistics_are_invalid() {
+        assert_eq!(
+            int64_bounds(&Statistics::int64(Some(1), Some(2), None, Some(0), false)).unwrap(),
+            Some((1, 2))
+        );
+        assert_eq!(
+            int64_bounds(&Statistics::int64(None, None, None, Some(0), false)).unwrap(),
+            None
+        );
+        assert!(matches!(
+            int64_bounds(&Statistics::int64(Some(1), None, None, Some(0), false)),
+            Err(MarketDataError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn stats_without_column_statistics() {
+        // Mimic a file written by another tool: no statistics, no version key
+        let candles = sample_candles();
+        let batch = candles_to_record_batch(&candles).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("legacy.parquet");
+        let props = WriterProperties::builder()
+            .set_statistics_enabled(EnabledStatistics::None)
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            std::fs::File::create(&path).unwrap(),
+            batch.schema(),
+            Some(props),
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let stats = read_parquet_stats(&path).unwrap();
+        assert_eq!(stats.row_count, 2);
+        assert_eq!(stats.first_timestamp, Some(candles[0].timestamp));
+        assert_eq!(stats.last_timestamp, Some(candles[1].timestamp));
+        assert_eq!(stats.schema_version, None);
+    }
+
+    #[test]
+    fn stats_empty_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.parquet");
+        write_parquet(&path, &[]).unwrap();
+
+        let stats = read_parquet_stats(&path).unwrap();
+        assert_eq!(stats.row_count, 0);
+        assert_eq!(stats.first_timestamp, None);
+    }
+
     #[test]
     fn decimal_precision_preserved() {
         let candle = Candle {
@@ -229,12 +966,14 @@ mod tests {
             low: dec!(0.0001),
             close: dec!(99999.9999),
             volume: 0,
+            vwap: None,
+            trade_count: None,
         };
 
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("precision.parquet");
 
-        write_parquet(&path, &[candle.clone()]).unwrap();
+        write_parquet(&path, std::slice::from_ref(&candle)).unwrap();
         let result = read_parquet(&path).unwrap();
 
         assert_eq!(result[0].open, dec!(123.4567));
@@ -242,4 +981,203 @@ mod tests {
         assert_eq!(result[0].low, dec!(0.0001));
         assert_eq!(result[0].close, dec!(99999.9999));
     }
+
+    /// Write `columns` as a Parquet file with default writer settings,
+    /// as another tool might.
+    fn write_foreign(path: &Path, columns: Vec<(&str, ArrayRef)>) {
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let file = std::fs::File::create(path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn reads_foreign_column_order_and_types() {
+        use arrow::array::{
+            Decimal128Array, Float64Array, Int32Array, TimestampMillisecondArray,
+            TimestampNanosecondArray, UInt64Array,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let expected = sample_candles();
+        let nanos: Vec<i64> = expected
+            .iter()
+            .map(|c| c.timestamp.timestamp_nanos_opt().unwrap())
+            .collect();
+
+        // Reordered, with an extra column, float prices and ns timestamps
+        let path = dir.path().join("floats.parquet");
+        write_foreign(
+            &path,
+            vec![
+                (
+                    "symbol",
+                    Arc::new(StringArray::from(vec!["AAPL"; 2])) as ArrayRef,
+                ),
+                ("volume", Arc::new(Int32Array::from(vec![1000, 2000]))),
+                (
+                    "close",
+                    Arc::new(Float64Array::from(vec![150.9999, 151.75])),
+                ),
+                ("low", Arc::new(Float64Array::from(vec![149.0001, 150.50]))),
+                ("high", Arc::new(Float64Array::from(vec![151.5678, 152.00]))),
+                (
+                    "open",
+                    Arc::new(Float64Array::from(vec![150.1234, 150.9999])),
+                ),
+                ("timestamp", Arc::new(TimestampNanosecondArray::from(nanos))),
+            ],
+        );
+        assert_eq!(read_parquet(&path).unwrap(), expected);
+
+        // Decimal128 prices, millisecond timestamps, unsigned volume
+        let millis: Vec<i64> = expected
+            .iter()
+            .map(|c| c.timestamp.timestamp_millis())
+            .collect();
+        let prices = |values: [i128; 2]| -> ArrayRef {
+            Arc::new(
+                Decimal128Array::from(values.to_vec())
+                    .with_precision_and_scale(18, 4)
+                    .unwrap(),
+            )
+        };
+        let path = dir.path().join("decimals.parquet");
+        write_foreign(
+            &path,
+            vec![
+                (
+                    "timestamp",
+                    Arc::new(TimestampMillisecondArray::from(millis).with_timezone("UTC"))
+                        as ArrayRef,
+                ),
+                ("open", prices([1_501_234, 1_509_999])),
+                ("high", prices([1_515_678, 1_520_000])),
+                ("low", prices([1_490_001, 1_505_000])),
+                ("close", prices([1_509_999, 1_517_500])),
+                ("volume", Arc::new(UInt64Array::from(vec![1000, 2000]))),
+            ],
+        );
+        assert_eq!(read_parquet(&path).unwrap(), expected);
+
+        // Pruning is skipped for non-microsecond files, filtering still applies
+        let second = expected[1].timestamp;
+        let filtered = decode_parquet_filtered(
+            Bytes::from(std::fs::read(&path).unwrap()),
+            &[(second, second)],
+            move |ts| *ts == second,
+        )
+        .unwrap();
+        assert_eq!(filtered, vec![expected[1].clone()]);
+
+        let stats = read_parquet_stats(&path).unwrap();
+        assert_eq!(stats.first_timestamp, Some(expected[0].timestamp));
+        assert_eq!(stats.last_timestamp, Some(expected[1].timestamp));
+    }
+
+    #[test]
+    fn vwap_and_trade_count_are_optional() {
+        use arrow::array::{Float64Array, UInt32Array};
+
+        let mut candles = sample_candles();
+        candles[0].vwap = Some(dec!(150.6543));
+        candles[0].trade_count = Some(87);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("v2.parquet");
+        write_parquet(&path, &candles).unwrap();
+        assert_eq!(read_parquet(&path).unwrap(), candles);
+
+        // Version 1 files have neither column
+        let expected = sample_candles();
+        let v1 = |column: &str| -> ArrayRef {
+            let batch = candles_to_record_batch(&expected).unwrap();
+            batch.column_by_name(column).unwrap().clone()
+        };
+        let path = dir.path().join("v1.parquet");
+        write_foreign(
+            &path,
+            ["timestamp", "open", "high", "low", "close", "volume"]
+                .into_iter()
+                .map(|name| (name, v1(name)))
+                .collect(),
+        );
+        assert_eq!(read_parquet(&path).unwrap(), expected);
+
+        // Foreign types, with nulls
+        let mut columns: Vec<(&str, ArrayRef)> =
+            ["timestamp", "open", "high", "low", "close", "volume"]
+                .into_iter()
+                .map(|name| (name, v1(name)))
+                .collect();
+        columns.push((
+            "vwap",
+            Arc::new(Float64Array::from(vec![Some(150.6543), None])),
+        ));
+        columns.push((
+            "trade_count",
+            Arc::new(UInt32Array::from(vec![Some(87), None])),
+        ));
+        let path = dir.path().join("foreign.parquet");
+        write_foreign(&path, columns);
+        assert_eq!(read_parquet(&path).unwrap(), candles);
+    }
+
+    #[test]
+    fn errors_name_file_and_column() {
+        use arrow::array::Float64Array;
+
+        let dir = tempfile::tempdir().unwrap();
+        let timestamps = || -> ArrayRef {
+            Arc::new(TimestampMicrosecondArray::from(vec![0]).with_timezone("UTC"))
+        };
+        let price = || -> ArrayRef { Arc::new(Float64Array::from(vec![1.0])) };
+
+        let missing = dir.path().join("missing.parquet");
+        write_foreign(
+            &missing,
+            vec![
+                ("timestamp", timestamps()),
+                ("open", price()),
+                ("high", price()),
+                ("low", price()),
+                ("close", price()),
+            ],
+        );
+        let err = read_parquet(&missing).unwrap_err().to_string();
+        assert!(err.contains("missing.parquet"), "{err}");
+        assert!(err.contains("missing column `volume`"), "{err}");
+
+        let garbled = dir.path().join("garbled.parquet");
+        write_foreign(
+            &garbled,
+            vec![
+                ("timestamp", timestamps()),
+                ("open", price()),
+                ("high", Arc::new(StringArray::from(vec!["n/a"]))),
+                ("low", price()),
+                ("close", price()),
+                ("volume", Arc::new(Int64Array::from(vec![1]))),
+            ],
+        );
+        let err = read_parquet(&garbled).unwrap_err().to_string();
+        assert!(err.contains("garbled.parquet"), "{err}");
+        assert!(err.contains("column `high`"), "{err}");
+        assert!(err.contains("\"n/a\""), "{err}");
+
+        let float_volume = dir.path().join("float_volume.parquet");
+        write_foreign(
+            &float_volume,
+            vec![
+                ("timestamp", timestamps()),
+                ("open", price()),
+                ("high", price()),
+                ("low", price()),
+                ("close", price()),
+                ("volume", price()),
+            ],
+        );
+        let err = read_parquet(&float_volume).unwrap_err().to_string();
+        assert!(err.contains("column `volume`: unsupported type"), "{err}");
+    }
 }
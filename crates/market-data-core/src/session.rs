@@ -1,7 +1,15 @@
-use chrono::{DateTime, Timelike, Utc};
+use chrono::{DateTime, NaiveTime, Timelike, Utc};
 use chrono_tz::America::New_York;
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
+use crate::candle::Timeframe;
+
+/// Convert a UTC timestamp to exchange-local (America/New_York) time.
+pub fn to_exchange_time(timestamp: &DateTime<Utc>) -> DateTime<Tz> {
+    timestamp.with_timezone(&New_York)
+}
+
 /// Trading session classification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Session {
@@ -14,10 +22,23 @@ pub enum Session {
 }
 
 impl Session {
+    /// All sessions in chronological order.
+    pub const ALL: [Session; 3] = [Session::PreMarket, Session::Regular, Session::AfterHours];
+
+    /// Exchange-local (ET) start (inclusive) and end (exclusive) of the session.
+    pub fn hours(&self) -> (NaiveTime, NaiveTime) {
+        let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        match self {
+            Session::PreMarket => (hm(4, 0), hm(9, 30)),
+            Session::Regular => (hm(9, 30), hm(16, 0)),
+            Session::AfterHours => (hm(16, 0), hm(20, 0)),
+        }
+    }
+
     /// Classify a UTC timestamp into a trading session.
     /// Returns `None` if the timestamp falls outside all sessions (before 4:00 or after 20:00 ET).
     pub fn classify(timestamp: &DateTime<Utc>) -> Option<Self> {
-        let et = timestamp.with_timezone(&New_York);
+        let et = to_exchange_time(timestamp);
         let hour = et.hour();
         let minute = et.minute();
         let total_minutes = hour * 60 + minute;
@@ -32,6 +53,16 @@ impl Session {
             _ => None,
         }
     }
+
+    /// Classify a bar by its start timestamp. A daily bar covers the regular
+    /// session of its date, so it is always [`Session::Regular`]; intraday
+    /// bars are classified as in [`Session::classify`].
+    pub fn classify_bar(start: &DateTime<Utc>, timeframe: Timeframe) -> Option<Self> {
+        match timeframe {
+            Timeframe::FiveMinute => Self::classify(start),
+            Timeframe::Daily => Some(Session::Regular),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +149,42 @@ mod tests {
         assert_eq!(Session::classify(&ts), Some(Session::Regular));
     }
 
+    #[test]
+    fn hours_agree_with_classify() {
+        for session in Session::ALL {
+            let (start, end) = session.hours();
+            let first = utc_from_et(2025, 1, 15, start.hour(), start.minute(), true);
+            let after = utc_from_et(2025, 1, 15, end.hour(), end.minute(), true);
+            assert_eq!(Session::classify(&first), Some(session));
+            assert_ne!(Session::classify(&after), Some(session));
+        }
+    }
+
+    #[test]
+    fn exchange_time_follows_dst() {
+        let winter = Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2025, 7, 15, 15, 0, 0).unwrap();
+        assert_eq!(to_exchange_time(&winter).hour(), 10);
+        assert_eq!(to_exchange_time(&summer).hour(), 11);
+    }
+
+    #[test]
+    fn daily_bars_are_regular_session() {
+        let open = Timeframe::session_open(chrono::NaiveDate::from_ymd_opt(2025, 7, 15).unwrap());
+        assert_eq!(
+            Session::classify_bar(&open, Timeframe::Daily),
+            Some(Session::Regular)
+        );
+        // A midnight-stamped daily bar is still the regular session, not an
+        // out-of-hours intraday bar
+        let midnight = Utc.with_ymd_and_hms(2025, 7, 15, 0, 0, 0).unwrap();
+        assert_eq!(Session::classify(&midnight), None);
+        assert_eq!(
+            Session::classify_bar(&midnight, Timeframe::Daily),
+            Some(Session::Regular)
+        );
+    }
+
     #[test]
     fn classify_dst_transition_boundary() {
         // March 9, 2025 is DST spring forward day (EST->EDT)
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::candle::Candle;
+use crate::error::MarketDataError;
+use crate::store::CandleStore;
+use crate::trading_calendar;
+
+/// Read-only access to per-day candle data.
+///
+/// Implemented by [`CandleStore`] and [`VecCandleSource`], so consumers can
+/// take `&dyn CandleSource` (or a generic) and be tested without Parquet files.
+/// Days are keyed by UTC date, as in the store's file layout.
+pub trait CandleSource: Send + Sync {
+    /// All candles for a symbol on one date, or `NoData` if the day is missing.
+    fn read_day(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Candle>, MarketDataError>;
+
+    /// All symbols with data, sorted.
+    fn list_symbols(&self) -> Result<Vec<String>, MarketDataError>;
+
+    /// All dates with data for a symbol, sorted ascending.
+    fn list_dates(&self, symbol: &str) -> Result<Vec<NaiveDate>, MarketDataError>;
+
+    /// Candles for a symbol across a date range (inclusive), sorted by
+    /// timestamp. Weekend dates and dates without data are skipped, as in
+    /// [`CandleStore::read_range`].
+    fn read_range(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let mut candles = Vec::new();
+        for date in self.list_dates(symbol)? {
+            if date >= start && date <= end && trading_calendar::is_weekday(date) {
+                candles.extend(self.read_day(symbol, date)?);
+            }
+        }
+        candles.sort_by_key(|c| c.timestamp);
+        Ok(candles)
+    }
+
+    /// The (earliest, latest) dates with data for a symbol, or `None`.
+    fn date_range(&self, symbol: &str) -> Result<Option<(NaiveDate, NaiveDate)>, MarketDataError> {
+        let dates = self.list_dates(symbol)?;
+        Ok(dates.first().copied().zip(dates.last().copied()))
+    }
+}
+
+impl CandleSource for CandleStore {
+    fn read_day(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Candle>, MarketDataError> {
+        CandleStore::read_day(self, symbol, date)
+    }
+
+    fn list_symbols(&self) -> Result<Vec<String>, MarketDataError> {
+        CandleStore::list_symbols(self)
+    }
+
+    fn list_dates(&self, symbol: &str) -> Result<Vec<NaiveDate>, MarketDataError> {
+        CandleStore::list_dates(self, symbol)
+    }
+
+    fn read_range(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        CandleStore::read_range(self, symbol, start, end)
+    }
+
+    fn date_range(&self, symbol: &str) -> Result<Option<(NaiveDate, NaiveDate)>, MarketDataError> {
+        CandleStore::date_range(self, symbol)
+    }
+}
+
+/// In-memory [`CandleSource`] for tests and small fixtures.
+#[derive(Debug, Clone, Default)]
+pub struct VecCandleSource {
+    days: BTreeMap<String, BTreeMap<NaiveDate, Vec<Candle>>>,
+}
+
+impl VecCandleSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add candles for a symbol, grouped into days by UTC date and merged
+    /// with any candles already held for those days.
+    pub fn insert(&mut self, symbol: &str, candles: impl IntoIterator<Item = Candle>) {
+        let days = self.days.entry(symbol.to_string()).or_default();
+        for candle in candles {
+            days.entry(candle.timestamp.date_naive())
+                .or_default()
+                .push(candle);
+        }
+        for day in days.values_mut() {
+            day.sort_by_key(|c| c.timestamp);
+        }
+    }
+
+    /// Builder-style [`VecCandleSource::insert`].
+    pub fn with(mut self, symbol: &str, candles: impl IntoIterator<Item = Candle>) -> Self {
+        self.insert(symbol, candles);
+        self
+    }
+
+    /// Replace one day's candles as-is, like [`CandleStore::write_day`].
+    pub fn insert_day(&mut self, symbol: &str, date: NaiveDate, candles: Vec<Candle>) {
+        self.days
+            .entry(symbol.to_string())
+            .or_default()
+            .insert(date, candles);
+    }
+}
+
+impl CandleSource for VecCandleSource {
+    fn read_day(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Candle>, MarketDataError> {
+        self.days
+            .get(symbol)
+            .and_then(|days| days.get(&date))
+            .cloned()
+            .ok_or_else(|| MarketDataError::NoData {
+                symbol: symbol.to_string(),
+                date,
+            })
+    }
+
+    fn list_symbols(&self) -> Result<Vec<String>, MarketDataError> {
+        Ok(self.days.keys().cloned().collect())
+    }
+
+    fn list_dates(&self, symbol: &str) -> Result<Vec<NaiveDate>, MarketDataError> {
+        Ok(self
+            .days
+            .get(symbol)
+            .map(|days| days.keys().copied().collect())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, d).unwrap()
+    }
+
+    fn candle(day: u32, hour: u32) -> Candle {
+        Candle {
+            timestamp: Utc.with_ymd_and_hms(2025, 1, day, hour, 30, 0).unwrap(),
+            open: dec!(150.00),
+            high: dec!(151.00),
+            low: dec!(149.00),
+            close: dec!(150.50),
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        }
+    }
+
+    /// Generic consumer code under test, written against the trait.
+    fn total_volume(source: &dyn CandleSource, symbol: &str) -> i64 {
+        let Some((start, end)) = source.date_range(symbol).unwrap() else {
+            return 0;
+        };
+        source
+            .read_range(symbol, start, end)
+            .unwrap()
+            .iter()
+            .map(|c| c.volume)
+            .sum()
+    }
+
+    #[test]
+    fn vec_source_groups_by_utc_date() {
+        let source = VecCandleSource::new()
+            .with("AAPL", [candle(15, 20), candle(15, 14), candle(16, 14)])
+            .with("MSFT", [candle(14, 14)]);
+
+        assert_eq!(source.list_symbols().unwrap(), vec!["AAPL", "MSFT"]);
+        assert_eq!(source.list_dates("AAPL").unwrap(), vec![date(15), date(16)]);
+        assert_eq!(
+            source.read_day("AAPL", date(15)).unwrap(),
+            vec![candle(15, 14), candle(15, 20)]
+        );
+        assert!(matches!(
+            source.read_day("AAPL", date(17)),
+            Err(MarketDataError::NoData { .. })
+        ));
+        assert_eq!(
+            source.read_range("AAPL", date(16), date(20)).unwrap(),
+            vec![candle(16, 14)]
+        );
+        assert_eq!(source.date_range("TSLA").unwrap(), None);
+        assert_eq!(total_volume(&source, "AAPL"), 3000);
+    }
+
+    #[test]
+    fn store_and_vec_source_agree() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        let mut source = VecCandleSource::new();
+        for day in [14, 15, 16] {
+            let candles = vec![candle(day, 14), candle(day, 15)];
+            store.write_day("AAPL", date(day), &candles).unwrap();
+            source.insert_day("AAPL", date(day), candles);
+        }
+
+        let sources: [&dyn CandleSource; 2] = [&store, &source];
+        for s in sources {
+            assert_eq!(s.list_symbols().unwrap(), vec!["AAPL"]);
+            assert_eq!(s.date_range("AAPL").unwrap(), Some((date(14), date(16))));
+            assert_eq!(s.read_range("AAPL", date(15), date(16)).unwrap().len(), 4);
+            assert_eq!(total_volume(s, "AAPL"), 6000);
+        }
+    }
+
+    #[test]
+    fn weekend_days_are_skipped_by_both_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        let mut source = VecCandleSource::new();
+        // Friday through Monday; the Saturday file holds Friday's evening bars
+        for day in [17, 18, 20] {
+            let candles = vec![candle(day, 0), candle(day, 14)];
+            store.write_day("AAPL", date(day), &candles).unwrap();
+            source.insert_day("AAPL", date(day), candles);
+        }
+
+        let expected = vec![candle(17, 0), candle(17, 14), candle(20, 0), candle(20, 14)];
+        let sources: [&dyn CandleSource; 2] = [&store, &source];
+        for s in sources {
+            assert_eq!(s.read_range("AAPL", date(17), date(20)).unwrap(), expected);
+        }
+    }
+}
@@ -1,37 +1,201 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use chrono::{NaiveDate, NaiveTime};
+use bytes::Bytes;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, Utc};
+use tracing::warn;
 
-use crate::candle::Candle;
+use crate::backend::{LocalBackend, StorageBackend};
+use crate::cache::{CacheStats, CandleCache};
+use crate::candle::{Candle, Timeframe};
+use crate::compact::PackPeriod;
+use crate::config::{CONFIG_FILE, StoreConfig};
 use crate::error::MarketDataError;
+use crate::query::{self, CandleFilter, Query, ReadReport};
 use crate::schema;
 use crate::session::Session;
 use crate::trading_calendar;
 
-/// Filesystem-backed store for 5-minute candle data in Parquet format.
+/// First window, in days, that [`CandleStore::read_last_n`] and
+/// [`CandleStore::read_next_n`] search before widening.
+const LOOKUP_WINDOW_DAYS: i64 = 7;
+
+/// One row of a cross-sectional [`CandleStore::snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotRow {
+    pub symbol: String,
+    /// Latest candle at or before the snapshot time, or `None` if the symbol
+    /// has no candle within the staleness limit.
+    pub candle: Option<Candle>,
+}
+
+/// Row count, time bounds and file size of a single day. See
+/// [`CandleStore::day_stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStats {
+    pub date: NaiveDate,
+    pub row_count: i64,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    /// Size in bytes of the file holding the day; for days in a pack file,
+    /// the whole pack.
+    pub file_size: u64,
+    /// `None` for files written before the schema version was recorded.
+    pub schema_version: Option<u32>,
+}
+
+/// How [`CandleStore::write_day`] treats candles it cannot store as given.
+///
+/// Both policies sort candles by timestamp and drop exact duplicate rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WritePolicy {
+    /// Reject the write if two different candles share a timestamp
+    /// ([`MarketDataError::ConflictingDuplicate`]) or a candle falls outside
+    /// the target UTC date ([`MarketDataError::OutsideDate`]).
+    #[default]
+    Strict,
+    /// Keep the last of conflicting candles and drop those outside the date.
+    Lenient,
+}
+
+/// Store for 5-minute candle data in Parquet format, one file per symbol and day.
+///
+/// Key layout: `{SYMBOL}/{YYYY}/{MM}/{YYYY-MM-DD}.parquet`, under `{root}/data/`
+/// for the default filesystem backend. Daily series are packed into one file
+/// per year, `{SYMBOL}/{YYYY}.parquet` (see [`CandleStore::write_daily`]), and
+/// old intraday history can be compacted into month or year packs
+/// (see [`CandleStore::compact`]). Reads and listings treat packed days like
+/// day files; a day file takes precedence over a month pack, which takes
+/// precedence over a year pack. Other [`StorageBackend`]s (in-memory,
+/// object stores) are plugged in with [`CandleStore::with_backend`].
 ///
-/// Directory layout: `{root}/data/{SYMBOL}/{YYYY}/{MM}/{YYYY-MM-DD}.parquet`
+/// Cloning is cheap; clones share the same backend and cache.
+#[derive(Debug, Clone)]
 pub struct CandleStore {
+    backend: Arc<dyn StorageBackend>,
     data_dir: PathBuf,
+    cache: Option<Arc<CandleCache>>,
+    config: StoreConfig,
+    write_policy: WritePolicy,
 }
 
 impl CandleStore {
     /// Create a store rooted at the given directory.
     /// The `data/` subdirectory is used automatically.
     pub fn new(root: impl AsRef<Path>) -> Self {
-        Self {
-            data_dir: root.as_ref().join("data"),
-        }
+        Self::from_data_dir(root.as_ref().join("data"))
     }
 
     /// Create a store pointing directly at the data directory (no `data/` suffix).
     pub fn from_data_dir(data_dir: impl AsRef<Path>) -> Self {
+        let data_dir = data_dir.as_ref().to_path_buf();
+        Self {
+            backend: Arc::new(LocalBackend::new(&data_dir)),
+            data_dir,
+            cache: None,
+            config: StoreConfig::default(),
+            write_policy: WritePolicy::default(),
+        }
+    }
+
+    /// Create a store over any storage backend.
+    pub fn with_backend(backend: Arc<dyn StorageBackend>) -> Self {
         Self {
-            data_dir: data_dir.as_ref().to_path_buf(),
+            backend,
+            data_dir: PathBuf::new(),
+            cache: None,
+            config: StoreConfig::default(),
+            write_policy: WritePolicy::default(),
+        }
+    }
+
+    /// Open a store rooted at the given directory, using the writer settings
+    /// persisted in its data directory, if any.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, MarketDataError> {
+        Self::new(root).load_config()
+    }
+
+    /// Use the writer settings persisted in the backend, if any.
+    pub fn load_config(mut self) -> Result<Self, MarketDataError> {
+        if let Some(data) = self.backend.get(CONFIG_FILE)? {
+            self.config = StoreConfig::from_json(&data)?;
         }
+        Ok(self)
+    }
+
+    /// Set the writer settings for subsequent writes. Call
+    /// [`CandleStore::save_config`] to persist them.
+    pub fn with_config(mut self, config: StoreConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &StoreConfig {
+        &self.config
+    }
+
+    /// Persist the current writer settings beside the data.
+    pub fn save_config(&self) -> Result<(), MarketDataError> {
+        self.config.validate()?;
+        self.backend
+            .put(CONFIG_FILE, Bytes::from(self.config.to_json()?))
+    }
+
+    /// Set how writes handle duplicate and out-of-date candles.
+    /// Defaults to [`WritePolicy::Strict`].
+    pub fn with_write_policy(mut self, policy: WritePolicy) -> Self {
+        self.write_policy = policy;
+        self
+    }
+
+    pub fn write_policy(&self) -> WritePolicy {
+        self.write_policy
+    }
+
+    /// Enable an in-process read-through cache of decoded day files,
+    /// bounded to roughly `max_bytes` of candle data.
+    pub fn with_cache(mut self, max_bytes: usize) -> Self {
+        self.cache = Some(Arc::new(CandleCache::new(max_bytes)));
+        self
+    }
+
+    /// Cache hit/miss counters, or `None` if caching is disabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|c| c.stats())
+    }
+
+    pub fn backend(&self) -> &Arc<dyn StorageBackend> {
+        &self.backend
+    }
+
+    /// Backend key of the Parquet file for a given symbol and date.
+    pub fn key(symbol: &str, date: NaiveDate) -> String {
+        format!("{symbol}/{}", date.format("%Y/%m/%Y-%m-%d.parquet"))
+    }
+
+    /// Backend key of the pack file holding a year of candles.
+    pub fn year_key(symbol: &str, year: i32) -> String {
+        format!("{symbol}/{year:04}.parquet")
+    }
+
+    /// Backend key of the pack file holding a month of candles.
+    pub fn month_key(symbol: &str, year: i32, month: u32) -> String {
+        format!("{symbol}/{year:04}/{month:02}.parquet")
+    }
+
+    /// Keys of the packs that may hold `date`, in order of precedence.
+    fn pack_keys(symbol: &str, date: NaiveDate) -> [String; 2] {
+        [
+            Self::month_key(symbol, date.year(), date.month()),
+            Self::year_key(symbol, date.year()),
+        ]
     }
 
     /// Path to the Parquet file for a given symbol and date.
+    /// Only meaningful for filesystem-backed stores; for other backends the
+    /// path is relative and mirrors [`CandleStore::key`].
     pub fn file_path(&self, symbol: &str, date: NaiveDate) -> PathBuf {
         self.data_dir
             .join(symbol)
@@ -40,261 +204,1071 @@ impl CandleStore {
             .join(format!("{}.parquet", date.format("%Y-%m-%d")))
     }
 
-    /// Check if data exists for a symbol on a given date.
+    /// Check if data exists for a symbol on a given date. A file that cannot
+    /// be read counts as missing and is logged; see
+    /// [`CandleStore::try_has_data`].
     pub fn has_data(&self, symbol: &str, date: NaiveDate) -> bool {
-        self.file_path(symbol, date).exists()
+        self.try_has_data(symbol, date).unwrap_or_else(|e| {
+            warn!(symbol, %date, error = %e, "treating unreadable data as missing");
+            false
+        })
     }
 
-    /// Find which weekdays in a range are missing data for a symbol.
+    /// Like [`CandleStore::has_data`], but fails if a pack that may hold the
+    /// date cannot be read.
+    pub fn try_has_data(&self, symbol: &str, date: NaiveDate) -> Result<bool, MarketDataError> {
+        if self.backend.head(&Self::key(symbol, date))?.is_some() {
+            return Ok(true);
+        }
+        Ok(self.find_pack(symbol, date)?.is_some())
+    }
+
+    /// Find which weekdays in a range are missing data for a symbol. If the
+    /// symbol's files cannot be listed or read, every weekday counts as
+    /// missing and the error is logged; see [`CandleStore::try_missing_dates`].
     pub fn missing_dates(&self, symbol: &str, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
-        trading_calendar::weekdays(start, end)
+        self.try_missing_dates(symbol, start, end)
+            .unwrap_or_else(|e| {
+                warn!(symbol, error = %e, "treating unreadable data as missing");
+                trading_calendar::weekdays(start, end)
+            })
+    }
+
+    /// Like [`CandleStore::missing_dates`], but fails if the symbol's files
+    /// cannot be listed or a pack cannot be read.
+    pub fn try_missing_dates(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<NaiveDate>, MarketDataError> {
+        let present: HashSet<NaiveDate> = self.list_dates(symbol)?.into_iter().collect();
+        Ok(trading_calendar::weekdays(start, end)
             .into_iter()
-            .filter(|d| !self.has_data(symbol, *d))
-            .collect()
+            .filter(|d| !present.contains(d))
+            .collect())
     }
 
     /// Write candles for a single date to a Parquet file.
-    /// Creates parent directories as needed. Overwrites if file already exists.
+    /// Overwrites if the file already exists; readers never see a partial file.
+    ///
+    /// Candles are sorted and deduplicated first; conflicting duplicates and
+    /// candles outside `date` are handled per the store's [`WritePolicy`].
     pub fn write_day(
         &self,
         symbol: &str,
         date: NaiveDate,
         candles: &[Candle],
     ) -> Result<(), MarketDataError> {
-        let path = self.file_path(symbol, date);
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
+        let candles = self.round_prices(symbol, candles.to_vec());
+        let candles = normalize_day(symbol, date, &candles, self.write_policy)?;
+        let data = schema::encode_parquet_with(&candles, &self.config)?;
+        let key = Self::key(symbol, date);
+        self.backend.put(&key, Bytes::from(data))?;
+        self.evict(&key);
+        Ok(())
+    }
+
+    /// Write daily candles into per-year pack files, merging with the days
+    /// already stored: each day present in `candles` replaces the stored day.
+    /// Each day is normalized as in [`CandleStore::write_day`].
+    /// Day files for those days are removed once their pack is written, so the
+    /// packed data is what reads see; a failed write leaves them in place.
+    pub fn write_daily(&self, symbol: &str, candles: &[Candle]) -> Result<(), MarketDataError> {
+        let mut years: BTreeMap<i32, BTreeMap<NaiveDate, Vec<Candle>>> = BTreeMap::new();
+        for candle in candles {
+            let date = candle.timestamp.date_naive();
+            years
+                .entry(date.year())
+                .or_default()
+                .entry(date)
+                .or_default()
+                .push(candle.clone());
+        }
+        let day_files: HashSet<String> = self.backend.list(symbol)?.into_iter().collect();
+
+        for (year, new_days) in years {
+            let key = Self::year_key(symbol, year);
+            let mut days: BTreeMap<NaiveDate, Vec<Candle>> = BTreeMap::new();
+            if let Some(data) = self.backend.get(&key)? {
+                for candle in schema::decode_parquet(data).map_err(|e| e.in_file(&key))? {
+                    days.entry(candle.timestamp.date_naive())
+                        .or_default()
+                        .push(candle);
+                }
+            }
+            let mut replaced = Vec::new();
+            for (date, day) in new_days {
+                let day = self.round_prices(symbol, day);
+                let day = normalize_day(symbol, date, &day, self.write_policy)?;
+                let day_key = Self::key(symbol, date);
+                if day_files.contains(&day_key) {
+                    replaced.push(day_key);
+                }
+                days.insert(date, day);
+            }
+
+            let mut merged: Vec<Candle> = days.into_values().flatten().collect();
+            merged.sort_by_key(|c| c.timestamp);
+            let data = schema::encode_pack_with(&merged, &self.config, Timeframe::Daily)?;
+            self.backend.put(&key, Bytes::from(data))?;
+            self.evict(&key);
+            for day_key in replaced {
+                self.backend.delete(&day_key)?;
+            }
         }
-        schema::write_parquet(&path, candles)
+        Ok(())
     }
 
-    /// Read all candles for a symbol on a specific date.
-    pub fn read_day(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Candle>, MarketDataError> {
-        let path = self.file_path(symbol, date);
-        if !path.exists() {
-            return Err(MarketDataError::NoData {
-                symbol: symbol.to_string(),
-                date,
-            });
+    /// Migrate a daily series stored before daily bars were labeled by the
+    /// session open of their date: re-stamp every bar at
+    /// [`Timeframe::session_open`], pack the series per year (marked as daily
+    /// bars) and remove leftover day files. Returns the number of bars whose
+    /// timestamp changed; running it again is a no-op rewrite.
+    ///
+    /// Fails with [`MarketDataError::InvalidData`] if any date holds more than
+    /// one candle, since the symbol is then not a daily series.
+    pub fn restamp_daily(&self, symbol: &str) -> Result<usize, MarketDataError> {
+        let mut candles = Vec::new();
+        let mut restamped = 0;
+        for date in self.list_dates(symbol)? {
+            let day = self.read_day(symbol, date)?;
+            if day.len() > 1 {
+                return Err(MarketDataError::InvalidData(format!(
+                    "{symbol} has {} candles on {date}; not a daily series",
+                    day.len()
+                )));
+            }
+            for mut candle in day {
+                let start = Timeframe::Daily.bar_start(&candle.timestamp);
+                if start != candle.timestamp {
+                    candle.timestamp = start;
+                    restamped += 1;
+                }
+                candles.push(candle);
+            }
         }
-        schema::read_parquet(&path)
+        self.write_daily(symbol, &candles)?;
+        Ok(restamped)
     }
 
-    /// Read candles for a symbol across a date range (inclusive).
-    /// Returns candles sorted by timestamp. Skips dates without data.
-    pub fn read_range(
-        &self,
-        symbol: &str,
-        start: NaiveDate,
-        end: NaiveDate,
-    ) -> Result<Vec<Candle>, MarketDataError> {
-        let dates = trading_calendar::weekdays(start, end);
-        let mut all_candles = Vec::new();
+    /// Re-encode the file holding a day with the store's current writer
+    /// settings and price rules. For a day in a pack file, the whole pack is
+    /// rewritten.
+    pub fn rewrite_day(&self, symbol: &str, date: NaiveDate) -> Result<(), MarketDataError> {
+        let day = self.get_day(symbol, date)?;
+        self.rewrite_file(&day.key, day.data)
+    }
 
-        for date in dates {
-            if self.has_data(symbol, date) {
-                let mut candles = schema::read_parquet(&self.file_path(symbol, date))?;
-                all_candles.append(&mut candles);
+    /// Re-encode every file of a symbol. Returns the number of files rewritten.
+    pub fn rewrite_symbol(&self, symbol: &str) -> Result<usize, MarketDataError> {
+        let mut count = 0;
+        for key in self.backend.list(symbol)? {
+            if parse_key(&key).is_none() {
+                continue;
             }
+            let data = self.backend.get(&key)?.ok_or_else(|| {
+                MarketDataError::Storage(format!("{key} disappeared while rewriting"))
+            })?;
+            self.rewrite_file(&key, data)?;
+            count += 1;
         }
+        Ok(count)
+    }
 
-        all_candles.sort_by_key(|c| c.timestamp);
-        Ok(all_candles)
+    /// Candles with prices rounded by the store's
+    /// [`PriceRules`](crate::precision::PriceRules), if any.
+    fn round_prices(&self, symbol: &str, mut candles: Vec<Candle>) -> Vec<Candle> {
+        if let Some(prices) = &self.config.prices {
+            prices.apply(symbol, &mut candles);
+        }
+        candles
     }
 
-    /// Read candles filtered by session type.
-    pub fn read_range_session(
-        &self,
-        symbol: &str,
-        start: NaiveDate,
-        end: NaiveDate,
-        session: Session,
-    ) -> Result<Vec<Candle>, MarketDataError> {
-        let candles = self.read_range(symbol, start, end)?;
-        Ok(candles
-            .into_iter()
-            .filter(|c| Session::classify(&c.timestamp) == Some(session))
-            .collect())
+    fn rewrite_file(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+        let candles = schema::decode_parquet(data.clone()).map_err(|e| e.in_file(key))?;
+        let symbol = key.split('/').next().unwrap_or_default();
+        let candles = self.round_prices(symbol, candles);
+        let data = match parse_key(key) {
+            Some(parsed) if parsed.is_pack() => {
+                let timeframe = schema::decode_timeframe(data).map_err(|e| e.in_file(key))?;
+                schema::encode_pack_with(&candles, &self.config, timeframe)?
+            }
+            _ => schema::encode_parquet_with(&candles, &self.config)?,
+        };
+        self.backend.put(key, Bytes::from(data))?;
+        self.evict(key);
+        Ok(())
     }
 
-    /// Read candles for a specific date within a time range (UTC).
-    pub fn read_time_range(
+    /// Key and raw bytes of the pack file holding `date`, if any.
+    fn find_pack(
         &self,
         symbol: &str,
         date: NaiveDate,
-        start_time: NaiveTime,
-        end_time: NaiveTime,
-    ) -> Result<Vec<Candle>, MarketDataError> {
-        let candles = self.read_day(symbol, date)?;
-        Ok(candles
-            .into_iter()
-            .filter(|c| {
-                let time = c.timestamp.time();
-                time >= start_time && time <= end_time
-            })
-            .collect())
-    }
-
-    /// List all symbols that have data in the store.
-    pub fn list_symbols(&self) -> Result<Vec<String>, MarketDataError> {
-        if !self.data_dir.exists() {
-            return Ok(Vec::new());
-        }
-
-        let mut symbols = Vec::new();
-        for entry in std::fs::read_dir(&self.data_dir)? {
-            let entry = entry?;
-            if entry.file_type()?.is_dir()
-                && let Some(name) = entry.file_name().to_str()
-            {
-                symbols.push(name.to_string());
+    ) -> Result<Option<(String, Bytes)>, MarketDataError> {
+        for key in Self::pack_keys(symbol, date) {
+            let Some(data) = self.backend.get(&key)? else {
+                continue;
+            };
+            let dates = schema::decode_pack_dates(data.clone()).map_err(|e| e.in_file(&key))?;
+            if dates.contains(&date) {
+                return Ok(Some((key, data)));
             }
         }
-        symbols.sort();
-        Ok(symbols)
+        Ok(None)
     }
 
-    /// List all dates with data for a given symbol, sorted ascending.
-    pub fn list_dates(&self, symbol: &str) -> Result<Vec<NaiveDate>, MarketDataError> {
-        let symbol_dir = self.data_dir.join(symbol);
-        if !symbol_dir.exists() {
-            return Ok(Vec::new());
+    /// Fetch the file holding a day, or `NoData` if it is missing.
+    fn get_day(&self, symbol: &str, date: NaiveDate) -> Result<DayFile, MarketDataError> {
+        let key = Self::key(symbol, date);
+        if let Some(data) = self.backend.get(&key)? {
+            return Ok(DayFile {
+                key,
+                data,
+                packed: false,
+                timeframe: Timeframe::FiveMinute,
+            });
         }
+        match self.find_pack(symbol, date)? {
+            Some((key, data)) => Ok(DayFile {
+                timeframe: schema::decode_timeframe(data.clone()).map_err(|e| e.in_file(&key))?,
+                key,
+                data,
+                packed: true,
+            }),
+            None => Err(MarketDataError::NoData {
+                symbol: symbol.to_string(),
+                date,
+            }),
+        }
+    }
 
-        let mut dates = Vec::new();
-
-        // Walk year directories
-        for year_entry in std::fs::read_dir(&symbol_dir)? {
-            let year_entry = year_entry?;
-            if !year_entry.file_type()?.is_dir() {
-                continue;
+    /// Resolve which file holds each stored day of a symbol, optionally within
+    /// an inclusive date range, with one listing and one fetch per pack.
+    ///
+    /// A pack that cannot be decoded is not an error here: the weekdays of
+    /// its period that no other file covers resolve to it, and reading them
+    /// reports the error.
+    pub(crate) fn index_days(
+        &self,
+        symbol: &str,
+        range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<DayIndex, MarketDataError> {
+        let in_range =
+            |date: &NaiveDate| range.is_none_or(|(start, end)| start <= *date && *date <= end);
+        // Day files rank before month packs, which rank before year packs
+        let mut days: BTreeMap<NaiveDate, (u8, String)> = BTreeMap::new();
+        let mut resolve = |date: NaiveDate, rank: u8, key: &str| {
+            let entry = days.entry(date).or_insert_with(|| (rank, key.to_string()));
+            if rank < entry.0 {
+                *entry = (rank, key.to_string());
             }
+        };
+        let mut packs = HashMap::new();
 
-            // Walk month directories
-            for month_entry in std::fs::read_dir(year_entry.path())? {
-                let month_entry = month_entry?;
-                if !month_entry.file_type()?.is_dir() {
+        for key in self.backend.list(symbol)? {
+            let Some(parsed) = parse_key(&key) else {
+                continue;
+            };
+            let (rank, period) = match parsed {
+                DataKey::Day(date) => {
+                    if in_range(&date) {
+                        resolve(date, 0, &key);
+                    }
                     continue;
                 }
-
-                // Walk parquet files
-                for file_entry in std::fs::read_dir(month_entry.path())? {
-                    let file_entry = file_entry?;
-                    let file_name = file_entry.file_name();
-                    let name = file_name.to_string_lossy();
-                    if let Some(date_str) = name.strip_suffix(".parquet")
-                        && let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
-                    {
-                        dates.push(date);
-                    }
+                DataKey::MonthPack(..) => (1, PackPeriod::Month),
+                DataKey::YearPack(_) => (2, PackPeriod::Year),
+            };
+            let first = parsed.first_day();
+            let last = period.end(first);
+            if range.is_some_and(|(start, end)| last < start || first > end) {
+                continue;
+            }
+            let Some(data) = self.backend.get(&key)? else {
+                continue;
+            };
+
+            let decoded = schema::decode_pack_dates(data.clone())
+                .and_then(|dates| Ok((dates, schema::decode_timeframe(data.clone())?)));
+            let dates = match decoded {
+                Ok((dates, timeframe)) => {
+                    packs.insert(key.clone(), Ok((data, timeframe)));
+                    dates
                 }
+                Err(e) => {
+                    let message = match e.in_file(&key) {
+                        MarketDataError::InvalidData(message) => message,
+                        other => format!("{key}: {other}"),
+                    };
+                    packs.insert(key.clone(), Err(message));
+                    trading_calendar::weekdays(first, last)
+                }
+            };
+            for date in dates.into_iter().filter(in_range) {
+                resolve(date, rank, &key);
             }
         }
 
-        dates.sort();
-        Ok(dates)
+        Ok(DayIndex {
+            days: days
+                .into_iter()
+                .map(|(date, (_, key))| (date, key))
+                .collect(),
+            packs,
+        })
     }
 
-    /// Get the date range (earliest, latest) for a symbol, or None if no data.
-    pub fn date_range(
+    /// First and last day any listed file for `symbol` can hold, from the keys
+    /// alone. `None` if the symbol has no files.
+    fn listed_span(&self, symbol: &str) -> Result<Option<(NaiveDate, NaiveDate)>, MarketDataError> {
+        let mut span: Option<(NaiveDate, NaiveDate)> = None;
+        for parsed in self
+            .backend
+            .list(symbol)?
+            .iter()
+            .filter_map(|key| parse_key(key))
+        {
+            let (first, last) = (parsed.first_day(), parsed.last_day());
+            span = Some(match span {
+                Some((lo, hi)) => (lo.min(first), hi.max(last)),
+                None => (first, last),
+            });
+        }
+        Ok(span)
+    }
+
+    /// The file `index` resolved for a day, or `NoData` if it has none.
+    fn indexed_day(
         &self,
+        index: &DayIndex,
         symbol: &str,
-    ) -> Result<Option<(NaiveDate, NaiveDate)>, MarketDataError> {
-        let dates = self.list_dates(symbol)?;
-        Ok(dates.first().copied().zip(dates.last().copied()))
+        date: NaiveDate,
+    ) -> Result<DayFile, MarketDataError> {
+        let no_data = || MarketDataError::NoData {
+            symbol: symbol.to_string(),
+            date,
+        };
+        let key = index.key(date).ok_or_else(no_data)?;
+        match index.packs.get(key) {
+            Some(Ok((data, timeframe))) => Ok(DayFile {
+                key: key.to_string(),
+                data: data.clone(),
+                packed: true,
+                timeframe: *timeframe,
+            }),
+            Some(Err(message)) => Err(MarketDataError::InvalidData(message.clone())),
+            None => {
+                let data = self.backend.get(key)?.ok_or_else(no_data)?;
+                Ok(DayFile {
+                    key: key.to_string(),
+                    data,
+                    packed: false,
+                    timeframe: Timeframe::FiveMinute,
+                })
+            }
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::{TimeZone, Timelike, Utc};
-    use rust_decimal_macros::dec;
 
-    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
-        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    /// The file holding a day, from `index` if given or else by probing the
+    /// day file and packs.
+    fn locate_day(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        index: Option<&DayIndex>,
+    ) -> Result<DayFile, MarketDataError> {
+        match index {
+            Some(index) => self.indexed_day(index, symbol, date),
+            None => self.get_day(symbol, date),
+        }
     }
 
-    fn make_candle(hour: u32, min: u32) -> Candle {
-        Candle {
-            timestamp: Utc.with_ymd_and_hms(2025, 1, 15, hour, min, 0).unwrap(),
-            open: dec!(150.00),
-            high: dec!(151.00),
-            low: dec!(149.00),
-            close: dec!(150.50),
-            volume: 1000,
-        }
+    /// Decode the candles of `date` from its file, keeping those that pass
+    /// `filter` if given. Pack files are pruned to the day's rows.
+    fn decode_day(
+        day: DayFile,
+        date: NaiveDate,
+        filter: Option<&CandleFilter>,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let decoded = match (day.packed, filter) {
+            (false, None) => schema::decode_parquet(day.data),
+            (false, Some(filter)) => {
+                let predicate = filter.clone();
+                schema::decode_parquet_filtered(day.data, &filter.ranges(date), move |ts| {
+                    predicate.matches(ts)
+                })
+            }
+            (true, filter) => {
+                let start = date.and_time(NaiveTime::MIN).and_utc();
+                let end = start + Duration::days(1) - Duration::microseconds(1);
+                let timeframe = day.timeframe;
+                // Session windows describe intraday bars, so daily packs are
+                // only pruned to the day and filtered per bar
+                let ranges: Vec<_> = match filter {
+                    Some(filter) if timeframe == Timeframe::FiveMinute => filter
+                        .ranges(date)
+                        .into_iter()
+                        .map(|(lo, hi)| (lo.max(start), hi.min(end)))
+                        .filter(|(lo, hi)| lo <= hi)
+                        .collect(),
+                    _ => vec![(start, end)],
+                };
+                if ranges.is_empty() {
+                    return Ok(Vec::new());
+                }
+                let predicate = filter.cloned();
+                schema::decode_parquet_filtered(day.data, &ranges, move |ts| {
+                    ts.date_naive() == date
+                        && predicate
+                            .as_ref()
+                            .is_none_or(|f| f.matches_bar(ts, timeframe))
+                })
+            }
+        };
+        decoded.map_err(|e| e.in_file(&day.key))
     }
 
-    fn make_candles_for_date(year: i32, month: u32, day: u32) -> Vec<Candle> {
-        vec![
-            Candle {
-                timestamp: Utc.with_ymd_and_hms(year, month, day, 14, 30, 0).unwrap(),
-                open: dec!(150.00),
-                high: dec!(151.00),
-                low: dec!(149.00),
-                close: dec!(150.50),
-                volume: 1000,
-            },
-            Candle {
-                timestamp: Utc.with_ymd_and_hms(year, month, day, 14, 35, 0).unwrap(),
-                open: dec!(150.50),
-                high: dec!(152.00),
-                low: dec!(150.00),
-                close: dec!(151.00),
-                volume: 2000,
-            },
-        ]
+    /// Read all candles for a symbol on a specific date.
+    pub fn read_day(&self, symbol: &str, date: NaiveDate) -> Result<Vec<Candle>, MarketDataError> {
+        Ok(self.read_day_bars(symbol, date)?.0)
     }
 
-    #[test]
-    fn file_path_format() {
-        let dir = tempfile::tempdir().unwrap();
-        let store = CandleStore::new(dir.path());
-        let path = store.file_path("AAPL", date(2025, 1, 15));
-        let expected = dir.path().join("data/AAPL/2025/01/2025-01-15.parquet");
-        assert_eq!(path, expected);
+    /// All candles of a day and their bar interval, through the cache if enabled.
+    pub(crate) fn read_day_bars(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+    ) -> Result<(Vec<Candle>, Timeframe), MarketDataError> {
+        self.read_bars(symbol, date, None)
     }
 
-    #[test]
-    fn has_data_false_before_write() {
-        let dir = tempfile::tempdir().unwrap();
-        let store = CandleStore::new(dir.path());
-        assert!(!store.has_data("AAPL", date(2025, 1, 15)));
+    /// All candles of a day, resolving its file through `index`.
+    #[cfg(feature = "async")]
+    pub(crate) fn read_day_indexed(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        index: &DayIndex,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        Ok(self.read_bars(symbol, date, Some(index))?.0)
     }
 
-    #[test]
-    fn write_and_read_day() {
-        let dir = tempfile::tempdir().unwrap();
-        let store = CandleStore::new(dir.path());
-        let candles = make_candles_for_date(2025, 1, 15);
+    /// [`CandleStore::read_day_bars`], resolving the day through `index` if given.
+    fn read_bars(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        index: Option<&DayIndex>,
+    ) -> Result<(Vec<Candle>, Timeframe), MarketDataError> {
+        let Some(cache) = &self.cache else {
+            let day = self.locate_day(symbol, date, index)?;
+            let timeframe = day.timeframe;
+            return Ok((Self::decode_day(day, date, None)?, timeframe));
+        };
+
+        // The cached day is valid while it came from the file that now holds
+        // the day and that file is unchanged
+        let source = match index.and_then(|index| index.key(date)) {
+            Some(key) => Some(key.to_string()),
+            None => {
+                let day_key = Self::key(symbol, date);
+                match cache.source(symbol, date) {
+                    // A day file written since would shadow the cached pack
+                    Some(source) if source != day_key && self.backend.head(&day_key)?.is_none() => {
+                        Some(source)
+                    }
+                    _ => Some(day_key),
+                }
+            }
+        };
+        if let Some(source) = source
+            && let Some(meta) = self.backend.head(&source)?
+            && let Some(cached) = cache.get(symbol, date, &source, meta.last_modified)
+        {
+            return Ok(cached);
+        }
 
-        store
-            .write_day("AAPL", date(2025, 1, 15), &candles)
-            .unwrap();
-        assert!(store.has_data("AAPL", date(2025, 1, 15)));
+        let day = self.locate_day(symbol, date, index)?;
+        let modified = self
+            .backend
+            .head(&day.key)?
+            .and_then(|meta| meta.last_modified);
+        let key = day.key.clone();
+        let timeframe = day.timeframe;
+        let candles = Self::decode_day(day, date, None)?;
+        cache.insert(symbol, date, &key, modified, candles.clone(), timeframe);
+        Ok((candles, timeframe))
+    }
 
-        let result = store.read_day("AAPL", date(2025, 1, 15)).unwrap();
-        assert_eq!(result, candles);
+    /// Drop cached days held by the file at `key`, after it is written or
+    /// removed.
+    pub(crate) fn evict(&self, key: &str) {
+        if let Some(cache) = &self.cache
+            && let Some(parsed) = parse_key(key)
+        {
+            let symbol = key.split('/').next().unwrap_or_default();
+            cache.invalidate(symbol, parsed.first_day(), parsed.last_day());
+        }
     }
 
-    #[test]
-    fn read_day_missing_returns_error() {
-        let dir = tempfile::tempdir().unwrap();
-        let store = CandleStore::new(dir.path());
-        let result = store.read_day("AAPL", date(2025, 1, 15));
-        assert!(result.is_err());
+    /// Read only the candles of one day that pass `filter`, letting the Parquet
+    /// reader skip pages outside the filter's time windows. With a cache
+    /// enabled, the whole day is read through the cache and filtered in memory.
+    pub(crate) fn read_day_filtered(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        filter: &CandleFilter,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        self.read_indexed(symbol, date, filter, None)
     }
 
-    #[test]
-    fn missing_dates_returns_weekdays_without_files() {
-        let dir = tempfile::tempdir().unwrap();
-        let store = CandleStore::new(dir.path());
+    /// Read the candles of one day that pass `filter`, resolving the day
+    /// through `index` if given, as a [`Query`] scan does.
+    pub(crate) fn read_indexed(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        filter: &CandleFilter,
+        index: Option<&DayIndex>,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        if self.cache.is_some() || filter.is_empty() {
+            let (mut candles, timeframe) = self.read_bars(symbol, date, index)?;
+            candles.retain(|c| filter.matches_bar(&c.timestamp, timeframe));
+            return Ok(candles);
+        }
 
-        // Write data for Wed Jan 15
-        store
-            .write_day(
-                "AAPL",
+        Self::decode_day(self.locate_day(symbol, date, index)?, date, Some(filter))
+    }
+
+    /// Start a composable query over a symbol's candles.
+    /// See [`Query`] for the available filters.
+    pub fn query(&self, symbol: &str) -> Query<'_> {
+        Query::new(self, symbol)
+    }
+
+    /// Read candles for a symbol across a date range (inclusive).
+    /// Returns candles sorted by timestamp. Skips weekend dates and dates
+    /// without data.
+    pub fn read_range(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        self.query(symbol).between(start, end).weekdays().collect()
+    }
+
+    /// Like [`CandleStore::read_range`], but skips day files that cannot be
+    /// read or decoded and reports them instead of failing the whole range.
+    pub fn read_range_lenient(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<(Vec<Candle>, ReadReport), MarketDataError> {
+        self.query(symbol)
+            .between(start, end)
+            .weekdays()
+            .collect_lenient()
+    }
+
+    /// Read candles filtered by session type.
+    pub fn read_range_session(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        session: Session,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        self.query(symbol)
+            .between(start, end)
+            .weekdays()
+            .session(session)
+            .collect()
+    }
+
+    /// Read the `n` candles immediately before `before` (exclusive), walking back
+    /// over as many days as needed. Weekends and missing days are skipped.
+    /// Returns candles sorted by timestamp, oldest first; fewer than `n` if the
+    /// store runs out of history.
+    pub fn read_last_n(
+        &self,
+        symbol: &str,
+        before: DateTime<Utc>,
+        n: usize,
+        session: Option<Session>,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        // Widen the window until it holds `n` candles or reaches back past the
+        // oldest file, so only packs near `before` are fetched
+        let Some((first, _)) = self.listed_span(symbol)? else {
+            return Ok(Vec::new());
+        };
+        let mut days = LOOKUP_WINDOW_DAYS;
+        loop {
+            let from = before - Duration::days(days);
+            let query = self
+                .query(symbol)
+                .timestamps(from..before)
+                .limit(n)
+                .descending();
+            let mut candles = match session {
+                Some(session) => query.session(session).collect()?,
+                None => query.collect()?,
+            };
+            if candles.len() >= n || from.date_naive() < first {
+                candles.reverse();
+                return Ok(candles);
+            }
+            days *= 4;
+        }
+    }
+
+    /// Read the `n` candles immediately after `after` (exclusive), walking
+    /// forward over as many days as needed. Returns candles sorted by timestamp.
+    pub fn read_next_n(
+        &self,
+        symbol: &str,
+        after: DateTime<Utc>,
+        n: usize,
+        session: Option<Session>,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let Some((_, last)) = self.listed_span(symbol)? else {
+            return Ok(Vec::new());
+        };
+        let mut days = LOOKUP_WINDOW_DAYS;
+        loop {
+            let to = after + Duration::days(days);
+            let query = self
+                .query(symbol)
+                .timestamps((Bound::Excluded(after), Bound::Excluded(to)))
+                .limit(n);
+            let candles = match session {
+                Some(session) => query.session(session).collect()?,
+                None => query.collect()?,
+            };
+            if candles.len() >= n || to.date_naive() > last {
+                return Ok(candles);
+            }
+            days *= 4;
+        }
+    }
+
+    /// Latest candle at or before `at`, looking back no further than `max_staleness`.
+    /// Steps back over weekends and missing days. Returns `None` if nothing
+    /// recent enough exists.
+    pub fn candle_as_of(
+        &self,
+        symbol: &str,
+        at: DateTime<Utc>,
+        max_staleness: Duration,
+    ) -> Result<Option<Candle>, MarketDataError> {
+        let oldest = at - max_staleness;
+        let mut candles = self
+            .query(symbol)
+            .between(oldest.date_naive(), at.date_naive())
+            .timestamps(oldest..=at)
+            .limit(1)
+            .descending()
+            .collect()?;
+        Ok(candles.pop())
+    }
+
+    /// Latest candle for each symbol as of `at`, one row per symbol in input order.
+    /// See [`CandleStore::candle_as_of`].
+    pub fn snapshot(
+        &self,
+        symbols: &[impl AsRef<str>],
+        at: DateTime<Utc>,
+        max_staleness: Duration,
+    ) -> Result<Vec<SnapshotRow>, MarketDataError> {
+        symbols
+            .iter()
+            .map(|symbol| {
+                let symbol = symbol.as_ref();
+                Ok(SnapshotRow {
+                    symbol: symbol.to_string(),
+                    candle: self.candle_as_of(symbol, at, max_staleness)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Read candles for a specific date within a time range (UTC).
+    pub fn read_time_range(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        let start = date.and_time(start_time).and_utc();
+        let end = date.and_time(end_time).and_utc();
+        self.read_day_filtered(symbol, date, &CandleFilter::within(start..=end))
+    }
+
+    /// Read candles for a specific trading date within a time range expressed
+    /// in exchange time (ET), inclusive on both ends.
+    ///
+    /// `date` is the exchange-local date, so late after-hours bars that fall on
+    /// the next UTC day are included.
+    pub fn read_time_range_et(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        if !self.try_has_data(symbol, date)? {
+            return Err(MarketDataError::NoData {
+                symbol: symbol.to_string(),
+                date,
+            });
+        }
+        self.read_range_time_of_day(symbol, date, date, start_time, end_time)
+    }
+
+    /// Read candles across a date range (inclusive) whose exchange-local (ET)
+    /// time of day falls within `[start_time, end_time]`.
+    ///
+    /// Dates are exchange-local trading dates. The UTC offset is resolved per
+    /// candle, so the window stays anchored to ET across DST transitions.
+    pub fn read_range_time_of_day(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        start_time: NaiveTime,
+        end_time: NaiveTime,
+    ) -> Result<Vec<Candle>, MarketDataError> {
+        // Evening ET bars land on the following UTC date, so read one extra day.
+        let read_end = end.succ_opt().unwrap_or(end);
+        let first = query::exchange_to_utc(start.and_time(NaiveTime::MIN), true);
+        let last = query::exchange_to_utc(read_end.and_time(NaiveTime::MIN), false);
+        self.query(symbol)
+            .between(start, read_end)
+            .timestamps(first..last)
+            .time_of_day(start_time, end_time)
+            .collect()
+    }
+
+    /// List all symbols that have data in the store.
+    pub fn list_symbols(&self) -> Result<Vec<String>, MarketDataError> {
+        self.backend.list_prefixes("")
+    }
+
+    /// List all dates with data for a given symbol, sorted ascending.
+    pub fn list_dates(&self, symbol: &str) -> Result<Vec<NaiveDate>, MarketDataError> {
+        let mut dates = Vec::new();
+        for key in self.backend.list(symbol)? {
+            match parse_key(&key) {
+                Some(DataKey::Day(date)) => dates.push(date),
+                Some(_) => {
+                    let Some(data) = self.backend.get(&key)? else {
+                        continue;
+                    };
+                    dates.extend(schema::decode_pack_dates(data).map_err(|e| e.in_file(&key))?);
+                }
+                None => {}
+            }
+        }
+
+        dates.sort();
+        dates.dedup();
+        Ok(dates)
+    }
+
+    /// Row count, timestamp range and file size for one day.
+    ///
+    /// A day file is summarized from its Parquet footer without decoding any
+    /// prices. A day in a month or year pack shares its footer with the other
+    /// days, so its rows are decoded to count them.
+    pub fn day_stats(&self, symbol: &str, date: NaiveDate) -> Result<DayStats, MarketDataError> {
+        let day = self.get_day(symbol, date)?;
+        let file_size = day.data.len() as u64;
+        let stats =
+            schema::decode_parquet_stats(day.data.clone()).map_err(|e| e.in_file(&day.key))?;
+        if !day.packed {
+            return Ok(DayStats {
+                date,
+                row_count: stats.row_count,
+                first_timestamp: stats.first_timestamp,
+                last_timestamp: stats.last_timestamp,
+                file_size,
+                schema_version: stats.schema_version,
+            });
+        }
+
+        let candles = Self::decode_day(day, date, None)?;
+        Ok(DayStats {
+            date,
+            row_count: candles.len() as i64,
+            first_timestamp: candles.first().map(|c| c.timestamp),
+            last_timestamp: candles.last().map(|c| c.timestamp),
+            file_size,
+            schema_version: stats.schema_version,
+        })
+    }
+
+    /// [`CandleStore::day_stats`] for every date with data, sorted by date.
+    pub fn symbol_stats(&self, symbol: &str) -> Result<Vec<DayStats>, MarketDataError> {
+        self.list_dates(symbol)?
+            .into_iter()
+            .map(|date| self.day_stats(symbol, date))
+            .collect()
+    }
+
+    /// Get the date range (earliest, latest) for a symbol, or None if no data.
+    pub fn date_range(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<(NaiveDate, NaiveDate)>, MarketDataError> {
+        let dates = self.list_dates(symbol)?;
+        Ok(dates.first().copied().zip(dates.last().copied()))
+    }
+}
+
+/// Sort a day's candles and drop exact duplicates, enforcing `policy` on
+/// conflicting duplicates and candles outside `date`.
+fn normalize_day(
+    symbol: &str,
+    date: NaiveDate,
+    candles: &[Candle],
+    policy: WritePolicy,
+) -> Result<Vec<Candle>, MarketDataError> {
+    let mut by_time: BTreeMap<DateTime<Utc>, &Candle> = BTreeMap::new();
+    for candle in candles {
+        if candle.timestamp.date_naive() != date {
+            match policy {
+                WritePolicy::Strict => {
+                    return Err(MarketDataError::OutsideDate {
+                        symbol: symbol.to_string(),
+                        date,
+                        timestamp: candle.timestamp,
+                    });
+                }
+                WritePolicy::Lenient => continue,
+            }
+        }
+        if let Some(previous) = by_time.insert(candle.timestamp, candle)
+            && previous != candle
+            && policy == WritePolicy::Strict
+        {
+            return Err(MarketDataError::ConflictingDuplicate {
+                symbol: symbol.to_string(),
+                timestamp: candle.timestamp,
+            });
+        }
+    }
+    Ok(by_time.into_values().cloned().collect())
+}
+
+/// Which file holds each stored day of a symbol. See [`CandleStore::index_days`].
+pub(crate) struct DayIndex {
+    /// Date -> key of the file that takes precedence for it.
+    days: BTreeMap<NaiveDate, String>,
+    /// Packs fetched while indexing, with their bar interval, or why they
+    /// could not be decoded.
+    packs: HashMap<String, Result<(Bytes, Timeframe), String>>,
+}
+
+impl DayIndex {
+    /// Dates with data, ascending.
+    pub(crate) fn dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.days.keys().copied()
+    }
+
+    /// Key of the file holding `date`.
+    pub(crate) fn key(&self, date: NaiveDate) -> Option<&str> {
+        self.days.get(&date).map(String::as_str)
+    }
+}
+
+/// A day's file as fetched from the backend.
+struct DayFile {
+    key: String,
+    data: Bytes,
+    /// Whether `data` is a multi-day pack rather than the day's own file.
+    packed: bool,
+    /// Bar interval of the candles in `data`.
+    timeframe: Timeframe,
+}
+
+/// Kind of data file a backend key names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DataKey {
+    /// `{SYMBOL}/{YYYY}/{MM}/{YYYY-MM-DD}.parquet`
+    Day(NaiveDate),
+    /// `{SYMBOL}/{YYYY}/{MM}.parquet`
+    MonthPack(i32, u32),
+    /// `{SYMBOL}/{YYYY}.parquet`
+    YearPack(i32),
+}
+
+impl DataKey {
+    pub(crate) fn is_pack(self) -> bool {
+        !matches!(self, Self::Day(_))
+    }
+
+    /// First day the file can hold.
+    fn first_day(self) -> NaiveDate {
+        match self {
+            Self::Day(date) => Some(date),
+            Self::MonthPack(year, month) => NaiveDate::from_ymd_opt(year, month, 1),
+            Self::YearPack(year) => NaiveDate::from_ymd_opt(year, 1, 1),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Last day the file can hold.
+    fn last_day(self) -> NaiveDate {
+        match self {
+            Self::Day(date) => Some(date),
+            Self::MonthPack(year, month) => NaiveDate::from_ymd_opt(year, month, 1)
+                .and_then(|first| first.checked_add_months(Months::new(1)))
+                .and_then(|next| next.pred_opt()),
+            Self::YearPack(year) => NaiveDate::from_ymd_opt(year, 12, 31),
+        }
+        .unwrap_or_default()
+    }
+}
+
+pub(crate) fn parse_key(key: &str) -> Option<DataKey> {
+    let digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    let parts: Vec<&str> = key.split('/').collect();
+    match parts.as_slice() {
+        [_, name] => {
+            let year = name.strip_suffix(".parquet")?;
+            digits(year, 4).then(|| DataKey::YearPack(year.parse().unwrap_or_default()))
+        }
+        [_, year, name] => {
+            let month = name.strip_suffix(".parquet")?;
+            (digits(year, 4) && digits(month, 2)).then(|| {
+                DataKey::MonthPack(
+                    year.parse().unwrap_or_default(),
+                    month.parse().unwrap_or_default(),
+                )
+            })
+        }
+        [_, _, _, name] => {
+            let date_str = name.strip_suffix(".parquet")?;
+            NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+                .ok()
+                .map(DataKey::Day)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MemoryBackend, ObjectMeta};
+    use chrono::{Datelike, TimeZone, Timelike};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn make_candle(hour: u32, min: u32) -> Candle {
+        Candle {
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 15, hour, min, 0).unwrap(),
+            open: dec!(150.00),
+            high: dec!(151.00),
+            low: dec!(149.00),
+            close: dec!(150.50),
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        }
+    }
+
+    fn make_candle_at(year: i32, month: u32, day: u32, hour: u32, min: u32) -> Candle {
+        Candle {
+            timestamp: Utc
+                .with_ymd_and_hms(year, month, day, hour, min, 0)
+                .unwrap(),
+            ..make_candle(hour, min)
+        }
+    }
+
+    fn make_candles_for_date(year: i32, month: u32, day: u32) -> Vec<Candle> {
+        vec![
+            Candle {
+                timestamp: Utc.with_ymd_and_hms(year, month, day, 14, 30, 0).unwrap(),
+                open: dec!(150.00),
+                high: dec!(151.00),
+                low: dec!(149.00),
+                close: dec!(150.50),
+                volume: 1000,
+                vwap: None,
+                trade_count: None,
+            },
+            Candle {
+                timestamp: Utc.with_ymd_and_hms(year, month, day, 14, 35, 0).unwrap(),
+                open: dec!(150.50),
+                high: dec!(152.00),
+                low: dec!(150.00),
+                close: dec!(151.00),
+                volume: 2000,
+                vwap: None,
+                trade_count: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn file_path_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        let path = store.file_path("AAPL", date(2025, 1, 15));
+        let expected = dir.path().join("data/AAPL/2025/01/2025-01-15.parquet");
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn has_data_false_before_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        assert!(!store.has_data("AAPL", date(2025, 1, 15)));
+    }
+
+    #[test]
+    fn write_and_read_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        let candles = make_candles_for_date(2025, 1, 15);
+
+        store
+            .write_day("AAPL", date(2025, 1, 15), &candles)
+            .unwrap();
+        assert!(store.has_data("AAPL", date(2025, 1, 15)));
+
+        let result = store.read_day("AAPL", date(2025, 1, 15)).unwrap();
+        assert_eq!(result, candles);
+    }
+
+    #[test]
+    fn read_day_missing_returns_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        let result = store.read_day("AAPL", date(2025, 1, 15));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn missing_dates_returns_weekdays_without_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        // Write data for Wed Jan 15
+        store
+            .write_day(
+                "AAPL",
                 date(2025, 1, 15),
                 &make_candles_for_date(2025, 1, 15),
             )
@@ -355,6 +1329,48 @@ mod tests {
         assert_eq!(result.len(), 2);
     }
 
+    #[test]
+    fn read_range_lenient_reports_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        for day in [14, 15] {
+            store
+                .write_day(
+                    "AAPL",
+                    date(2025, 1, day),
+                    &make_candles_for_date(2025, 1, day),
+                )
+                .unwrap();
+        }
+        // Readable Parquet, but not a candle file
+        let batch = arrow::record_batch::RecordBatch::try_from_iter([(
+            "price",
+            std::sync::Arc::new(arrow::array::Int64Array::from(vec![1])) as arrow::array::ArrayRef,
+        )])
+        .unwrap();
+        let file = std::fs::File::create(store.file_path("AAPL", date(2025, 1, 16))).unwrap();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let (candles, report) = store
+            .read_range_lenient("AAPL", date(2025, 1, 13), date(2025, 1, 17))
+            .unwrap();
+        assert_eq!(candles.len(), 4);
+        assert_eq!(report.skipped.len(), 1);
+        let message = report.skipped[0].error.to_string();
+        assert!(
+            message.contains("AAPL/2025/01/2025-01-16.parquet"),
+            "{message}"
+        );
+        assert!(message.contains("missing column `timestamp`"), "{message}");
+
+        let clean = store
+            .read_range_lenient("AAPL", date(2025, 1, 13), date(2025, 1, 15))
+            .unwrap();
+        assert!(clean.1.is_complete());
+    }
+
     #[test]
     fn read_range_session_filters() {
         let dir = tempfile::tempdir().unwrap();
@@ -370,6 +1386,8 @@ mod tests {
                 low: dec!(149.00),
                 close: dec!(150.50),
                 volume: 500,
+                vwap: None,
+                trade_count: None,
             }, // 16:00 ET = AfterHours
         ];
 
@@ -399,6 +1417,179 @@ mod tests {
         assert_eq!(after.len(), 1);
     }
 
+    #[test]
+    fn read_last_n_spans_weekends() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        // Fri Jan 10 and Mon Jan 13, 2025
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 10),
+                &make_candles_for_date(2025, 1, 10),
+            )
+            .unwrap();
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 13),
+                &make_candles_for_date(2025, 1, 13),
+            )
+            .unwrap();
+
+        let before = Utc.with_ymd_and_hms(2025, 1, 13, 14, 35, 0).unwrap();
+        let result = store.read_last_n("AAPL", before, 2, None).unwrap();
+        assert_eq!(
+            result.iter().map(|c| c.timestamp).collect::<Vec<_>>(),
+            vec![
+                Utc.with_ymd_and_hms(2025, 1, 10, 14, 35, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 13, 14, 30, 0).unwrap(),
+            ]
+        );
+
+        // Asking for more than exists returns what is available
+        let all = store.read_last_n("AAPL", before, 10, None).unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
+    #[test]
+    fn read_last_n_with_session_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        // 14:30 UTC = Regular, 22:00 UTC = AfterHours
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 15),
+                &[make_candle(14, 30), make_candle(22, 0)],
+            )
+            .unwrap();
+
+        let before = Utc.with_ymd_and_hms(2025, 1, 16, 0, 0, 0).unwrap();
+        let result = store
+            .read_last_n("AAPL", before, 1, Some(Session::Regular))
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].timestamp.hour(), 14);
+    }
+
+    #[test]
+    fn read_next_n_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 15),
+                &make_candles_for_date(2025, 1, 15),
+            )
+            .unwrap();
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 16),
+                &make_candles_for_date(2025, 1, 16),
+            )
+            .unwrap();
+
+        let after = Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap();
+        let result = store.read_next_n("AAPL", after, 2, None).unwrap();
+        assert_eq!(
+            result.iter().map(|c| c.timestamp).collect::<Vec<_>>(),
+            vec![
+                Utc.with_ymd_and_hms(2025, 1, 15, 14, 35, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 16, 14, 30, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn candle_as_of_steps_back_over_weekend() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        // Fri Jan 10, 2025
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 10),
+                &make_candles_for_date(2025, 1, 10),
+            )
+            .unwrap();
+
+        // Mon Jan 13 before the open
+        let at = Utc.with_ymd_and_hms(2025, 1, 13, 14, 0, 0).unwrap();
+        let candle = store
+            .candle_as_of("AAPL", at, Duration::days(4))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            candle.timestamp,
+            Utc.with_ymd_and_hms(2025, 1, 10, 14, 35, 0).unwrap()
+        );
+
+        // Too stale for a one-day limit
+        assert!(
+            store
+                .candle_as_of("AAPL", at, Duration::days(1))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn candle_as_of_is_inclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 15),
+                &make_candles_for_date(2025, 1, 15),
+            )
+            .unwrap();
+
+        let at = Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap();
+        let candle = store
+            .candle_as_of("AAPL", at, Duration::hours(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(candle.timestamp, at);
+    }
+
+    #[test]
+    fn snapshot_one_row_per_symbol() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 15),
+                &make_candles_for_date(2025, 1, 15),
+            )
+            .unwrap();
+        store
+            .write_day(
+                "MSFT",
+                date(2025, 1, 14),
+                &make_candles_for_date(2025, 1, 14),
+            )
+            .unwrap();
+
+        let at = Utc.with_ymd_and_hms(2025, 1, 15, 20, 55, 0).unwrap();
+        let rows = store
+            .snapshot(&["MSFT", "AAPL", "TSLA"], at, Duration::days(3))
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].symbol, "MSFT");
+        assert_eq!(rows[0].candle.as_ref().unwrap().timestamp.day(), 14);
+        assert_eq!(rows[1].candle.as_ref().unwrap().timestamp.day(), 15);
+        assert!(rows[2].candle.is_none());
+    }
+
     #[test]
     fn read_time_range_filters() {
         let dir = tempfile::tempdir().unwrap();
@@ -412,18 +1603,160 @@ mod tests {
         ];
 
         store
-            .write_day("AAPL", date(2025, 1, 15), &candles)
+            .write_day("AAPL", date(2025, 1, 15), &candles)
+            .unwrap();
+
+        let result = store
+            .read_time_range(
+                "AAPL",
+                date(2025, 1, 15),
+                NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(15, 30, 0).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn read_time_range_et_converts_from_exchange_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        // Jan 15 is EST (UTC-5): 15:00 UTC = 10:00 ET
+        let candles = vec![make_candle(14, 30), make_candle(15, 0), make_candle(15, 30)];
+        store
+            .write_day("AAPL", date(2025, 1, 15), &candles)
+            .unwrap();
+
+        let result = store
+            .read_time_range_et(
+                "AAPL",
+                date(2025, 1, 15),
+                NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(10, 30, 0).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].timestamp.hour(), 15);
+    }
+
+    #[test]
+    fn read_time_range_et_includes_next_utc_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        // 19:30 ET on Jan 15 is 00:30 UTC on Jan 16
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 16),
+                &[make_candle_at(2025, 1, 16, 0, 30)],
+            )
+            .unwrap();
+        store
+            .write_day("AAPL", date(2025, 1, 15), &[make_candle(14, 30)])
+            .unwrap();
+
+        let result = store
+            .read_time_range_et(
+                "AAPL",
+                date(2025, 1, 15),
+                NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].timestamp.day(), 16);
+    }
+
+    #[test]
+    fn friday_evening_bars_in_saturday_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        let friday = date(2025, 1, 17);
+
+        // 19:30 ET on Friday Jan 17 is 00:30 UTC on Saturday Jan 18
+        store
+            .write_day("AAPL", friday, &[make_candle_at(2025, 1, 17, 14, 30)])
+            .unwrap();
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 18),
+                &[make_candle_at(2025, 1, 18, 0, 30)],
+            )
+            .unwrap();
+        let (seven, five_to_eight) = (
+            NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(19, 55, 0).unwrap(),
+        );
+
+        let evening = store
+            .read_time_range_et("AAPL", friday, seven, five_to_eight)
+            .unwrap();
+        assert_eq!(evening.len(), 1);
+        assert_eq!(evening[0].timestamp.day(), 18);
+        assert_eq!(
+            store
+                .read_range_time_of_day("AAPL", date(2025, 1, 13), friday, seven, five_to_eight)
+                .unwrap(),
+            evening
+        );
+
+        // Plain range reads still skip weekend dates
+        assert_eq!(
+            store
+                .read_range("AAPL", friday, date(2025, 1, 19))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn read_range_time_of_day_handles_dst() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+
+        // Winter (EST): 10:00 ET = 15:00 UTC
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 15),
+                &[
+                    make_candle_at(2025, 1, 15, 14, 0),
+                    make_candle_at(2025, 1, 15, 15, 0),
+                ],
+            )
+            .unwrap();
+        // Summer (EDT): 10:00 ET = 14:00 UTC
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 7, 15),
+                &[
+                    make_candle_at(2025, 7, 15, 14, 0),
+                    make_candle_at(2025, 7, 15, 15, 0),
+                ],
+            )
             .unwrap();
 
         let result = store
-            .read_time_range(
+            .read_range_time_of_day(
                 "AAPL",
-                date(2025, 1, 15),
-                NaiveTime::from_hms_opt(15, 0, 0).unwrap(),
-                NaiveTime::from_hms_opt(15, 30, 0).unwrap(),
+                date(2025, 1, 1),
+                date(2025, 7, 31),
+                NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(10, 30, 0).unwrap(),
             )
             .unwrap();
-        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result.iter().map(|c| c.timestamp).collect::<Vec<_>>(),
+            vec![
+                Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 7, 15, 14, 0, 0).unwrap(),
+            ]
+        );
     }
 
     #[test]
@@ -491,6 +1824,677 @@ mod tests {
         assert_eq!(range, Some((date(2025, 1, 15), date(2025, 2, 3))));
     }
 
+    #[test]
+    fn day_and_symbol_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path());
+        let candles = make_candles_for_date(2025, 1, 15);
+        store
+            .write_day("AAPL", date(2025, 1, 15), &candles)
+            .unwrap();
+        store
+            .write_day(
+                "AAPL",
+                date(2025, 1, 16),
+                &make_candles_for_date(2025, 1, 16)[..1],
+            )
+            .unwrap();
+
+        let stats = store.day_stats("AAPL", date(2025, 1, 15)).unwrap();
+        assert_eq!(stats.date, date(2025, 1, 15));
+        assert_eq!(stats.row_count, 2);
+        assert_eq!(stats.first_timestamp, Some(candles[0].timestamp));
+        assert_eq!(stats.last_timestamp, Some(candles[1].timestamp));
+        assert!(stats.file_size > 0);
+        assert_eq!(stats.schema_version, Some(schema::SCHEMA_VERSION));
+
+        let all = store.symbol_stats("AAPL").unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].row_count, 1);
+
+        assert!(store.day_stats("AAPL", date(2025, 1, 17)).is_err());
+    }
+
+    #[test]
+    fn cache_serves_repeated_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path()).with_cache(1 << 20);
+        let candles = make_candles_for_date(2025, 1, 15);
+        store
+            .write_day("AAPL", date(2025, 1, 15), &candles)
+            .unwrap();
+
+        // Clones share the cache
+        let handle = store.clone();
+        assert_eq!(store.read_day("AAPL", date(2025, 1, 15)).unwrap(), candles);
+        assert_eq!(
+            handle
+                .read_range("AAPL", date(2025, 1, 13), date(2025, 1, 17))
+                .unwrap(),
+            candles
+        );
+        let regular = handle
+            .read_range_session(
+                "AAPL",
+                date(2025, 1, 15),
+                date(2025, 1, 15),
+                Session::Regular,
+            )
+            .unwrap();
+        assert_eq!(regular.len(), 2);
+
+        let stats = store.cache_stats().unwrap();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[test]
+    fn cache_sees_rewritten_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path()).with_cache(1 << 20);
+        let d = date(2025, 1, 15);
+        let candles = make_candles_for_date(2025, 1, 15);
+        store.write_day("AAPL", d, &candles).unwrap();
+        assert_eq!(store.read_day("AAPL", d).unwrap().len(), 2);
+
+        // Writes evict the day, however coarse the filesystem's mtimes
+        store.write_day("AAPL", d, &candles[..1]).unwrap();
+        assert_eq!(store.read_day("AAPL", d).unwrap().len(), 1);
+
+        store.write_daily("AAPL", &candles[1..]).unwrap();
+        assert_eq!(store.read_day("AAPL", d).unwrap(), &candles[1..]);
+        store.rewrite_symbol("AAPL").unwrap();
+        assert_eq!(store.read_day("AAPL", d).unwrap(), &candles[1..]);
+    }
+
+    #[test]
+    fn cache_checks_the_file_that_holds_the_day() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone()).with_cache(1 << 20);
+        let (d14, d15) = (date(2025, 1, 14), date(2025, 1, 15));
+        store
+            .write_day("AAPL", d14, &make_candles_for_date(2025, 1, 14))
+            .unwrap();
+        store
+            .compact("AAPL", PackPeriod::Month, date(2025, 2, 1))
+            .unwrap();
+        let mut daily = make_candles_for_date(2025, 1, 15);
+        daily.truncate(1);
+        store.write_daily("AAPL", &daily).unwrap();
+
+        // The month pack exists but the 15th is in the year pack
+        assert_eq!(store.read_day("AAPL", d15).unwrap(), daily);
+
+        // Rewriting the year pack behind the store's back is still noticed
+        daily[0].close = dec!(1.00);
+        let data = schema::encode_pack_with(&daily, store.config(), Timeframe::Daily).unwrap();
+        backend
+            .put(&CandleStore::year_key("AAPL", 2025), Bytes::from(data))
+            .unwrap();
+        assert_eq!(store.read_day("AAPL", d15).unwrap(), daily);
+    }
+
+    #[test]
+    fn cache_disabled_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(CandleStore::new(dir.path()).cache_stats().is_none());
+    }
+
+    #[test]
+    fn memory_backend_store() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone()).with_cache(1 << 20);
+        for day in [14, 15, 16] {
+            store
+                .write_day(
+                    "AAPL",
+                    date(2025, 1, day),
+                    &make_candles_for_date(2025, 1, day),
+                )
+                .unwrap();
+        }
+        store
+            .write_day(
+                "MSFT",
+                date(2025, 1, 15),
+                &make_candles_for_date(2025, 1, 15),
+            )
+            .unwrap();
+
+        assert_eq!(
+            backend.list("MSFT").unwrap(),
+            vec![CandleStore::key("MSFT", date(2025, 1, 15))]
+        );
+        assert_eq!(store.list_symbols().unwrap(), vec!["AAPL", "MSFT"]);
+        assert_eq!(
+            store.date_range("AAPL").unwrap(),
+            Some((date(2025, 1, 14), date(2025, 1, 16)))
+        );
+        assert_eq!(
+            store
+                .read_range("AAPL", date(2025, 1, 13), date(2025, 1, 17))
+                .unwrap()
+                .len(),
+            6
+        );
+        assert_eq!(
+            store.missing_dates("AAPL", date(2025, 1, 13), date(2025, 1, 17)),
+            vec![date(2025, 1, 13), date(2025, 1, 17)]
+        );
+        assert_eq!(
+            store
+                .day_stats("AAPL", date(2025, 1, 15))
+                .unwrap()
+                .row_count,
+            2
+        );
+        assert!(matches!(
+            store.read_day("AAPL", date(2025, 1, 17)),
+            Err(MarketDataError::NoData { .. })
+        ));
+    }
+
+    #[test]
+    fn config_persists_and_rewrite_reencodes() {
+        use crate::config::StoreConfig;
+        use parquet::basic::Compression;
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let codec = |store: &CandleStore, d: NaiveDate| {
+            let data = store.backend().get(&CandleStore::key("AAPL", d)).unwrap();
+            let reader = SerializedFileReader::new(data.unwrap()).unwrap();
+            reader.metadata().row_group(0).column(1).compression()
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let d = date(2025, 1, 15);
+        let candles = make_candles_for_date(2025, 1, 15);
+        CandleStore::new(dir.path())
+            .write_day("AAPL", d, &candles)
+            .unwrap();
+
+        let store = CandleStore::open(dir.path())
+            .unwrap()
+            .with_config(StoreConfig::archival(9));
+        store.save_config().unwrap();
+        assert!(matches!(codec(&store, d), Compression::SNAPPY));
+
+        let reopened = CandleStore::open(dir.path()).unwrap();
+        assert_eq!(reopened.config(), &StoreConfig::archival(9));
+        assert_eq!(reopened.list_symbols().unwrap(), vec!["AAPL"]);
+        assert_eq!(reopened.rewrite_symbol("AAPL").unwrap(), 1);
+        assert!(matches!(codec(&reopened, d), Compression::ZSTD(_)));
+        assert_eq!(reopened.read_day("AAPL", d).unwrap(), candles);
+    }
+
+    #[test]
+    fn price_rules_round_writes_and_rewrites() {
+        use crate::config::StoreConfig;
+        use crate::precision::{PriceRule, PriceRules};
+
+        let store = CandleStore::with_backend(Arc::new(MemoryBackend::new()));
+        let d = date(2025, 1, 15);
+        let mut noisy = make_candles_for_date(2025, 1, 15);
+        noisy[0].open = dec!(150.11999511718750);
+        noisy[0].vwap = Some(dec!(150.456700));
+        store.write_day("AAPL", d, &noisy).unwrap();
+        assert_eq!(
+            store.read_day("AAPL", d).unwrap()[0].open.to_string(),
+            "150.11999511718750"
+        );
+
+        // Rules apply to existing files on rewrite, then to every write
+        let rules = PriceRules::default().with_symbol(
+            "VIX",
+            PriceRule {
+                scale: 2,
+                tick_size: Some(dec!(0.05)),
+            },
+        );
+        let store = store.with_config(StoreConfig {
+            prices: Some(rules),
+            ..StoreConfig::default()
+        });
+        store.rewrite_symbol("AAPL").unwrap();
+        let day = store.read_day("AAPL", d).unwrap();
+        assert_eq!(day[0].open.to_string(), "150.12");
+        assert_eq!(day[0].high.to_string(), "151");
+        assert_eq!(day[0].vwap.unwrap().to_string(), "150.4567");
+
+        store
+            .write_daily("VIX", &[daily_candle(d, dec!(15.82))])
+            .unwrap();
+        assert_eq!(store.read_day("VIX", d).unwrap()[0].close, dec!(15.80));
+    }
+
+    fn daily_candle(date: NaiveDate, close: Decimal) -> Candle {
+        Candle {
+            timestamp: Timeframe::session_open(date),
+            close,
+            ..make_candle(14, 30)
+        }
+    }
+
+    #[test]
+    fn daily_series_packed_per_year() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone());
+        let days = trading_calendar::weekdays(date(2023, 12, 1), date(2024, 1, 31));
+        let candles: Vec<Candle> = days.iter().map(|&d| daily_candle(d, dec!(13.5))).collect();
+        store.write_daily("VIX", &candles).unwrap();
+
+        assert_eq!(
+            backend.list("VIX").unwrap(),
+            vec![
+                CandleStore::year_key("VIX", 2023),
+                CandleStore::year_key("VIX", 2024)
+            ]
+        );
+        assert_eq!(store.list_dates("VIX").unwrap(), days);
+        assert_eq!(
+            store
+                .read_range("VIX", date(2023, 12, 28), date(2024, 1, 3))
+                .unwrap(),
+            candles[19..24].to_vec()
+        );
+        assert!(store.has_data("VIX", date(2024, 1, 2)));
+        assert!(!store.has_data("VIX", date(2024, 1, 6)));
+        assert_eq!(
+            store.missing_dates("VIX", date(2024, 1, 29), date(2024, 2, 2)),
+            vec![date(2024, 2, 1), date(2024, 2, 2)]
+        );
+        assert_eq!(
+            store
+                .read_range_session("VIX", date(2024, 1, 1), date(2024, 1, 31), Session::Regular)
+                .unwrap()
+                .len(),
+            23
+        );
+        let stats = store.day_stats("VIX", date(2024, 1, 2)).unwrap();
+        assert_eq!(stats.row_count, 1);
+        assert_eq!(stats.first_timestamp, Some(candles[22].timestamp));
+
+        // New and corrected days are merged into the existing pack
+        store
+            .write_daily(
+                "VIX",
+                &[
+                    daily_candle(date(2024, 1, 2), dec!(14.0)),
+                    daily_candle(date(2024, 2, 1), dec!(15.0)),
+                ],
+            )
+            .unwrap();
+        assert_eq!(store.list_dates("VIX").unwrap().len(), days.len() + 1);
+        assert_eq!(
+            store.read_day("VIX", date(2024, 1, 2)).unwrap()[0].close,
+            dec!(14.0)
+        );
+        assert_eq!(
+            store.read_day("VIX", date(2024, 1, 3)).unwrap()[0].close,
+            dec!(13.5)
+        );
+        assert_eq!(store.rewrite_symbol("VIX").unwrap(), 2);
+        assert_eq!(store.list_dates("VIX").unwrap().len(), days.len() + 1);
+    }
+
+    #[test]
+    fn write_daily_replaces_day_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CandleStore::new(dir.path()).with_cache(1 << 20);
+        let d = date(2024, 1, 2);
+        store
+            .write_day("VIX", d, &[daily_candle(d, dec!(13.0))])
+            .unwrap();
+        assert_eq!(store.read_day("VIX", d).unwrap()[0].close, dec!(13.0));
+
+        store
+            .write_daily("VIX", &[daily_candle(d, dec!(14.0))])
+            .unwrap();
+        assert!(!store.file_path("VIX", d).exists());
+        assert!(dir.path().join("data/VIX/2024.parquet").exists());
+        assert_eq!(store.list_dates("VIX").unwrap(), vec![d]);
+        assert_eq!(store.read_day("VIX", d).unwrap()[0].close, dec!(14.0));
+    }
+
+    /// Backend whose writes of pack files fail.
+    #[derive(Debug, Default)]
+    struct FailingPacks(MemoryBackend);
+
+    impl StorageBackend for FailingPacks {
+        fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+            self.0.get(key)
+        }
+
+        fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+            self.0.head(key)
+        }
+
+        fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+            match parse_key(key) {
+                Some(parsed) if parsed.is_pack() => {
+                    Err(MarketDataError::Storage(format!("cannot write {key}")))
+                }
+                _ => self.0.put(key, data),
+            }
+        }
+
+        fn delete(&self, key: &str) -> Result<(), MarketDataError> {
+            self.0.delete(key)
+        }
+
+        fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            self.0.list(prefix)
+        }
+
+        fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            self.0.list_prefixes(prefix)
+        }
+    }
+
+    /// Backend that counts fetches.
+    #[derive(Debug, Default)]
+    struct CountingGets {
+        inner: MemoryBackend,
+        gets: std::sync::atomic::AtomicUsize,
+    }
+
+    impl StorageBackend for CountingGets {
+        fn get(&self, key: &str) -> Result<Option<Bytes>, MarketDataError> {
+            self.gets.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.inner.get(key)
+        }
+
+        fn head(&self, key: &str) -> Result<Option<ObjectMeta>, MarketDataError> {
+            self.inner.head(key)
+        }
+
+        fn put(&self, key: &str, data: Bytes) -> Result<(), MarketDataError> {
+            self.inner.put(key, data)
+        }
+
+        fn delete(&self, key: &str) -> Result<(), MarketDataError> {
+            self.inner.delete(key)
+        }
+
+        fn list(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            self.inner.list(prefix)
+        }
+
+        fn list_prefixes(&self, prefix: &str) -> Result<Vec<String>, MarketDataError> {
+            self.inner.list_prefixes(prefix)
+        }
+    }
+
+    #[test]
+    fn range_reads_fetch_each_pack_once() {
+        let backend = Arc::new(CountingGets::default());
+        let store = CandleStore::with_backend(backend.clone());
+        let days = trading_calendar::weekdays(date(2024, 1, 1), date(2024, 3, 29));
+        let candles: Vec<Candle> = days.iter().map(|&d| daily_candle(d, dec!(13.5))).collect();
+        store.write_daily("VIX", &candles).unwrap();
+        // A corrected day file shadows its packed day
+        let fixed = daily_candle(date(2024, 2, 1), dec!(14.0));
+        store
+            .write_day("VIX", date(2024, 2, 1), std::slice::from_ref(&fixed))
+            .unwrap();
+
+        let gets = || backend.gets.load(std::sync::atomic::Ordering::Relaxed);
+        let before = gets();
+        let read = store
+            .read_range("VIX", date(2024, 1, 1), date(2024, 3, 31))
+            .unwrap();
+        assert_eq!(read.len(), days.len());
+        assert_eq!(
+            read[days.iter().position(|&d| d == date(2024, 2, 1)).unwrap()],
+            fixed
+        );
+        // The year pack and the one day file
+        assert_eq!(gets() - before, 2);
+
+        let before = gets();
+        assert_eq!(
+            store
+                .query("VIX")
+                .session(Session::Regular)
+                .descending()
+                .limit(5)
+                .collect()
+                .unwrap()
+                .len(),
+            5
+        );
+        assert_eq!(gets() - before, 1);
+    }
+
+    #[test]
+    fn lookbacks_fetch_only_nearby_packs() {
+        let backend = Arc::new(CountingGets::default());
+        let store = CandleStore::with_backend(backend.clone());
+        let days = trading_calendar::weekdays(date(2020, 1, 1), date(2024, 12, 31));
+        let candles: Vec<Candle> = days.iter().map(|&d| daily_candle(d, dec!(13.5))).collect();
+        store.write_daily("VIX", &candles).unwrap();
+
+        let gets = || backend.gets.load(std::sync::atomic::Ordering::Relaxed);
+        let at = |d: NaiveDate| d.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let before = gets();
+        let last = store
+            .read_last_n("VIX", at(date(2024, 6, 3)), 3, None)
+            .unwrap();
+        assert_eq!(
+            last.iter()
+                .map(|c| c.timestamp.date_naive())
+                .collect::<Vec<_>>(),
+            vec![date(2024, 5, 29), date(2024, 5, 30), date(2024, 5, 31)]
+        );
+        assert_eq!(gets() - before, 1);
+
+        let before = gets();
+        let next = store
+            .read_next_n("VIX", at(date(2021, 12, 30)), 3, None)
+            .unwrap();
+        assert_eq!(
+            next.iter()
+                .map(|c| c.timestamp.date_naive())
+                .collect::<Vec<_>>(),
+            vec![date(2021, 12, 30), date(2021, 12, 31), date(2022, 1, 3)]
+        );
+        assert_eq!(gets() - before, 2);
+
+        // Running out of history still ends the walk
+        let first = store
+            .read_last_n("VIX", at(date(2020, 1, 3)), 10, None)
+            .unwrap();
+        assert_eq!(first.len(), 2);
+    }
+
+    #[test]
+    fn failed_daily_write_keeps_day_files() {
+        let store = CandleStore::with_backend(Arc::new(FailingPacks::default()));
+        let d = date(2024, 1, 2);
+        store
+            .write_day("VIX", d, &[daily_candle(d, dec!(13.0))])
+            .unwrap();
+
+        assert!(
+            store
+                .write_daily("VIX", &[daily_candle(d, dec!(14.0))])
+                .is_err()
+        );
+        assert_eq!(store.read_day("VIX", d).unwrap()[0].close, dec!(13.0));
+    }
+
+    #[test]
+    fn unreadable_pack_is_reported_not_missing() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone());
+        backend
+            .put(
+                &CandleStore::year_key("VIX", 2024),
+                Bytes::from_static(b"not parquet"),
+            )
+            .unwrap();
+
+        assert!(store.try_has_data("VIX", date(2024, 1, 2)).is_err());
+        assert!(!store.has_data("VIX", date(2024, 1, 2)));
+        assert!(
+            store
+                .try_missing_dates("VIX", date(2024, 1, 1), date(2024, 1, 5))
+                .is_err()
+        );
+        assert_eq!(
+            store
+                .missing_dates("VIX", date(2024, 1, 1), date(2024, 1, 5))
+                .len(),
+            5
+        );
+        assert!(
+            store
+                .read_range("VIX", date(2024, 1, 1), date(2024, 1, 5))
+                .is_err()
+        );
+        let (candles, report) = store
+            .read_range_lenient("VIX", date(2024, 1, 1), date(2024, 1, 5))
+            .unwrap();
+        assert!(candles.is_empty());
+        assert_eq!(report.skipped.len(), 5);
+    }
+
+    #[test]
+    fn restamp_daily_migrates_legacy_bars() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone());
+        let legacy = |d: NaiveDate| Candle {
+            timestamp: d.and_hms_opt(14, 30, 0).unwrap().and_utc(),
+            ..daily_candle(d, dec!(13.5))
+        };
+
+        // A pre-flag 2024 pack and pre-pack day files, all stamped 14:30 UTC
+        let packed: Vec<Candle> = trading_calendar::weekdays(date(2024, 7, 1), date(2024, 7, 5))
+            .into_iter()
+            .map(legacy)
+            .collect();
+        let data =
+            schema::encode_pack_with(&packed, &StoreConfig::default(), Timeframe::FiveMinute)
+                .unwrap();
+        backend
+            .put(&CandleStore::year_key("VIX", 2024), Bytes::from(data))
+            .unwrap();
+        for d in [date(2025, 1, 15), date(2025, 7, 15)] {
+            store.write_day("VIX", d, &[legacy(d)]).unwrap();
+        }
+        let summer = date(2025, 7, 15);
+        let (nine_thirty, ten) = (
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+        );
+        assert!(
+            store
+                .read_range_time_of_day("VIX", summer, summer, nine_thirty, nine_thirty)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Five summer bars and one in July 2025; the January bar already sits at 9:30 ET
+        assert_eq!(store.restamp_daily("VIX").unwrap(), 6);
+        assert_eq!(
+            backend.list("VIX").unwrap(),
+            vec![
+                CandleStore::year_key("VIX", 2024),
+                CandleStore::year_key("VIX", 2025)
+            ]
+        );
+        assert_eq!(
+            store.read_day("VIX", summer).unwrap(),
+            vec![daily_candle(summer, dec!(13.5))]
+        );
+        assert_eq!(store.restamp_daily("VIX").unwrap(), 0);
+
+        // Daily bars cover the regular session, whatever the filter
+        for store in [store.clone(), store.clone().with_cache(1 << 20)] {
+            let all = store.list_dates("VIX").unwrap().len();
+            let (first, last) = (date(2024, 1, 1), date(2025, 12, 31));
+            assert_eq!(
+                store
+                    .read_range_session("VIX", first, last, Session::Regular)
+                    .unwrap()
+                    .len(),
+                all
+            );
+            assert!(
+                store
+                    .read_range_session("VIX", first, last, Session::AfterHours)
+                    .unwrap()
+                    .is_empty()
+            );
+            assert_eq!(
+                store
+                    .read_range_time_of_day("VIX", summer, summer, ten, ten)
+                    .unwrap()
+                    .len(),
+                1
+            );
+        }
+
+        store
+            .write_day("AAPL", summer, &make_candles_for_date(2025, 7, 15))
+            .unwrap();
+        assert!(matches!(
+            store.restamp_daily("AAPL"),
+            Err(MarketDataError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn write_day_normalizes_candles() {
+        let store = CandleStore::with_backend(Arc::new(MemoryBackend::new()));
+        let d = date(2025, 1, 15);
+        let candles = make_candles_for_date(2025, 1, 15);
+
+        // Unsorted with an exact duplicate
+        let messy = vec![candles[1].clone(), candles[0].clone(), candles[1].clone()];
+        store.write_day("AAPL", d, &messy).unwrap();
+        assert_eq!(store.read_day("AAPL", d).unwrap(), candles);
+
+        let mut conflicting = candles[1].clone();
+        conflicting.close = dec!(999.00);
+        let outside = make_candle_at(2025, 1, 16, 0, 5);
+        let err = store
+            .write_day("AAPL", d, &[candles[1].clone(), conflicting.clone()])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MarketDataError::ConflictingDuplicate { timestamp, .. } if timestamp == candles[1].timestamp
+        ));
+        let err = store
+            .write_day("AAPL", d, &[candles[0].clone(), outside.clone()])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MarketDataError::OutsideDate { date, .. } if date == d
+        ));
+        // Rejected writes leave the stored day untouched
+        assert_eq!(store.read_day("AAPL", d).unwrap(), candles);
+
+        let lenient = store.clone().with_write_policy(WritePolicy::Lenient);
+        lenient
+            .write_day(
+                "AAPL",
+                d,
+                &[
+                    candles[1].clone(),
+                    outside,
+                    candles[0].clone(),
+                    conflicting.clone(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            store.read_day("AAPL", d).unwrap(),
+            vec![candles[0].clone(), conflicting]
+        );
+    }
+
     #[test]
     fn date_range_empty() {
         let dir = tempfile::tempdir().unwrap();
@@ -1,13 +1,17 @@
 use chrono::{Datelike, NaiveDate, Weekday};
 
+/// Whether `date` falls on Monday through Friday.
+pub fn is_weekday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
 /// Returns all weekdays (Mon-Fri) in the inclusive date range [start, end].
 pub fn weekdays(start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
     let mut dates = Vec::new();
     let mut current = start;
     while current <= end {
-        match current.weekday() {
-            Weekday::Sat | Weekday::Sun => {}
-            _ => dates.push(current),
+        if is_weekday(current) {
+            dates.push(current);
         }
         current = current.succ_opt().unwrap_or(current);
         if current == start && current > end {
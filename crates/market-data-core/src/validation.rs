@@ -0,0 +1,639 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::candle::{Candle, Timeframe};
+use crate::error::MarketDataError;
+use crate::session::Session;
+use crate::store::CandleStore;
+
+/// How serious a validation issue is, least serious first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing, but expected in normal data.
+    Info,
+    /// Suspicious data that readers will still accept.
+    Warning,
+    /// Data that is wrong or cannot be read.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "INFO",
+            Self::Warning => "WARN",
+            Self::Error => "ERROR",
+        })
+    }
+}
+
+/// One stored day, as seen by a [`ValidationRule`].
+#[derive(Debug, Clone, Copy)]
+pub struct DayData<'a> {
+    pub symbol: &'a str,
+    /// UTC date of the day file, as in the store's layout.
+    pub date: NaiveDate,
+    /// Bar interval of the stored candles.
+    pub timeframe: Timeframe,
+    /// Candles in stored order.
+    pub candles: &'a [Candle],
+}
+
+/// A problem reported by a rule, before it is tied to a symbol and date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Finding {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Info,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+}
+
+/// A check run against every day a [`Validator`] reads.
+///
+/// Implement it to add team-specific checks and register them with
+/// [`Validator::with_rule`].
+pub trait ValidationRule: Send + Sync {
+    /// Short identifier shown in reports, e.g. `zero-volume`.
+    fn name(&self) -> &str;
+
+    /// Check one day and return what is wrong with it, if anything.
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding>;
+}
+
+/// A [`Finding`] for a specific day, tagged with the rule that raised it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub symbol: String,
+    pub date: NaiveDate,
+    /// [`ValidationRule::name`] of the rule, or `read` for unreadable files.
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {}: {} [{}]",
+            self.severity, self.symbol, self.date, self.message, self.rule
+        )
+    }
+}
+
+/// Result of a validation run. See [`Validator::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Issues in the order found: by symbol, then date, then rule.
+    pub issues: Vec<Issue>,
+    /// Number of days read.
+    pub days_checked: usize,
+}
+
+impl ValidationReport {
+    /// True if no rule raised anything.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Number of issues of exactly this severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+
+    /// Severity of the worst issue, or `None` for a clean report.
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.issues.iter().map(|i| i.severity).max()
+    }
+
+    /// True if any issue is an [`Severity::Error`].
+    pub fn has_errors(&self) -> bool {
+        self.max_severity() == Some(Severity::Error)
+    }
+}
+
+/// Runs a set of [`ValidationRule`]s over stored days.
+///
+/// [`Validator::default`] has the standard rules; start from
+/// [`Validator::empty`] to pick rules one by one.
+pub struct Validator {
+    rules: Vec<Box<dyn ValidationRule>>,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::empty()
+            .with_rule(EmptyDay)
+            .with_rule(SortedTimestamps)
+            .with_rule(DuplicateTimestamps)
+            .with_rule(ZeroVolume)
+            .with_rule(NegativeVolume)
+            .with_rule(PositivePrices)
+            .with_rule(OhlcInvariants)
+            .with_rule(BarGrid)
+            .with_rule(InSession)
+            .with_rule(FileDate)
+    }
+}
+
+impl Validator {
+    /// A validator with no rules. Unreadable files are still reported.
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Add a rule, run after the ones already registered.
+    pub fn with_rule(mut self, rule: impl ValidationRule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Names of the registered rules, in run order.
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name()).collect()
+    }
+
+    /// Run every rule over one day of candles.
+    pub fn check_day(&self, day: &DayData<'_>) -> Vec<Issue> {
+        self.rules
+            .iter()
+            .flat_map(|rule| {
+                rule.check(day).into_iter().map(|finding| Issue {
+                    symbol: day.symbol.to_string(),
+                    date: day.date,
+                    rule: rule.name().to_string(),
+                    severity: finding.severity,
+                    message: finding.message,
+                })
+            })
+            .collect()
+    }
+
+    /// Validate every stored day of the given symbols. Files that cannot be
+    /// read are reported as errors; failing to list a symbol's dates fails
+    /// the run.
+    pub fn validate(
+        &self,
+        store: &CandleStore,
+        symbols: &[impl AsRef<str>],
+    ) -> Result<ValidationReport, MarketDataError> {
+        let mut report = ValidationReport::default();
+        for symbol in symbols {
+            let symbol = symbol.as_ref();
+            for date in store.list_dates(symbol)? {
+                report.days_checked += 1;
+                match store.read_day_bars(symbol, date) {
+                    Ok((candles, timeframe)) => report.issues.extend(self.check_day(&DayData {
+                        symbol,
+                        date,
+                        timeframe,
+                        candles: &candles,
+                    })),
+                    Err(e) => report.issues.push(Issue {
+                        symbol: symbol.to_string(),
+                        date,
+                        rule: "read".to_string(),
+                        severity: Severity::Error,
+                        message: format!("failed to read: {e}"),
+                    }),
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// A stored day with no candles.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmptyDay;
+
+impl ValidationRule for EmptyDay {
+    fn name(&self) -> &str {
+        "empty"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        if day.candles.is_empty() {
+            vec![Finding::warning("empty file")]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Candles must be stored in ascending timestamp order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SortedTimestamps;
+
+impl ValidationRule for SortedTimestamps {
+    fn name(&self) -> &str {
+        "unsorted"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        day.candles
+            .windows(2)
+            .position(|w| w[1].timestamp < w[0].timestamp)
+            .map(|i| Finding::error(format!("timestamps not ascending at index {}", i + 1)))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// No two candles of a day may share a timestamp.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DuplicateTimestamps;
+
+impl ValidationRule for DuplicateTimestamps {
+    fn name(&self) -> &str {
+        "duplicate-timestamp"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        let mut timestamps: Vec<_> = day.candles.iter().map(|c| c.timestamp).collect();
+        timestamps.sort();
+        let duplicates: Vec<_> = timestamps
+            .windows(2)
+            .filter(|w| w[0] == w[1])
+            .map(|w| w[0])
+            .collect();
+        match duplicates.first() {
+            Some(first) => vec![Finding::error(format!(
+                "{} duplicate timestamp(s), first at {first}",
+                duplicates.len()
+            ))],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Intraday candles with zero volume. Daily series such as CBOE indices
+/// report no volume at all and are skipped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZeroVolume;
+
+impl ValidationRule for ZeroVolume {
+    fn name(&self) -> &str {
+        "zero-volume"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        if day.timeframe == Timeframe::Daily {
+            return Vec::new();
+        }
+        failing(day, Severity::Warning, "with zero volume", |c| {
+            c.volume == 0
+        })
+    }
+}
+
+/// High must be at or above, and low at or below, both open and close.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OhlcInvariants;
+
+impl ValidationRule for OhlcInvariants {
+    fn name(&self) -> &str {
+        "ohlc"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        failing(
+            day,
+            Severity::Error,
+            "with high below open/close or low above open/close",
+            |c| c.high < c.open.max(c.close) || c.low > c.open.min(c.close),
+        )
+    }
+}
+
+/// Prices must be positive.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PositivePrices;
+
+impl ValidationRule for PositivePrices {
+    fn name(&self) -> &str {
+        "non-positive-price"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        failing(day, Severity::Error, "with a zero or negative price", |c| {
+            [c.open, c.high, c.low, c.close]
+                .iter()
+                .any(|p| *p <= Decimal::ZERO)
+        })
+    }
+}
+
+/// Volume must not be negative.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NegativeVolume;
+
+impl ValidationRule for NegativeVolume {
+    fn name(&self) -> &str {
+        "negative-volume"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        failing(day, Severity::Error, "with negative volume", |c| {
+            c.volume < 0
+        })
+    }
+}
+
+/// Timestamps must be bar starts on the timeframe grid in exchange time:
+/// the 5-minute grid, or the 9:30 ET open for daily bars.
+/// See [`Timeframe::bar_start`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BarGrid;
+
+impl ValidationRule for BarGrid {
+    fn name(&self) -> &str {
+        "off-grid"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        let what = match day.timeframe {
+            Timeframe::FiveMinute => "off the 5-minute grid",
+            Timeframe::Daily => "not stamped at the 9:30 ET open (see restamp-daily)",
+        };
+        failing(day, Severity::Warning, what, |c| {
+            !c.is_aligned(day.timeframe)
+        })
+    }
+}
+
+/// Intraday bars must fall within a trading session (4:00-20:00 ET).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InSession;
+
+impl ValidationRule for InSession {
+    fn name(&self) -> &str {
+        "outside-session"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        failing(day, Severity::Warning, "outside 4:00-20:00 ET", |c| {
+            Session::classify_bar(&c.timestamp, day.timeframe).is_none()
+        })
+    }
+}
+
+/// Candles must belong to the date of the file holding them. Files are
+/// keyed by UTC date, so late after-hours bars belong to the next day's file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileDate;
+
+impl ValidationRule for FileDate {
+    fn name(&self) -> &str {
+        "date-mismatch"
+    }
+
+    fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+        failing(
+            day,
+            Severity::Error,
+            "dated outside the file's UTC date",
+            |c| c.timestamp.date_naive() != day.date,
+        )
+    }
+}
+
+/// One finding counting the candles that match `bad`, naming the first.
+fn failing(
+    day: &DayData<'_>,
+    severity: Severity,
+    what: &str,
+    bad: impl Fn(&Candle) -> bool,
+) -> Vec<Finding> {
+    let mut matches = day.candles.iter().filter(|c| bad(c));
+    let Some(first) = matches.next() else {
+        return Vec::new();
+    };
+    vec![Finding {
+        severity,
+        message: format!(
+            "{} candle(s) {what}, first at {}",
+            1 + matches.count(),
+            first.timestamp
+        ),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MemoryBackend, StorageBackend};
+    use bytes::Bytes;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, d).unwrap()
+    }
+
+    fn day(d: u32, n: i64) -> Vec<Candle> {
+        (0..n)
+            .map(|i| Candle {
+                timestamp: Utc.with_ymd_and_hms(2025, 1, d, 14, 30, 0).unwrap()
+                    + Duration::minutes(5 * i),
+                open: dec!(150.00),
+                high: dec!(151.00),
+                low: dec!(149.00),
+                close: dec!(150.50),
+                volume: 1000,
+                vwap: None,
+                trade_count: None,
+            })
+            .collect()
+    }
+
+    fn check(candles: &[Candle], timeframe: Timeframe) -> Vec<(String, Severity)> {
+        Validator::default()
+            .check_day(&DayData {
+                symbol: "AAPL",
+                date: date(15),
+                timeframe,
+                candles,
+            })
+            .into_iter()
+            .map(|i| (i.rule, i.severity))
+            .collect()
+    }
+
+    #[test]
+    fn standard_rules() {
+        assert!(check(&day(15, 78), Timeframe::FiveMinute).is_empty());
+        assert_eq!(
+            check(&[], Timeframe::FiveMinute),
+            vec![("empty".to_string(), Severity::Warning)]
+        );
+
+        let mut candles = day(15, 4);
+        candles.swap(1, 2);
+        candles[3] = candles[2].clone();
+        candles[0].volume = 0;
+        assert_eq!(
+            check(&candles, Timeframe::FiveMinute),
+            vec![
+                ("unsorted".to_string(), Severity::Error),
+                ("duplicate-timestamp".to_string(), Severity::Error),
+                ("zero-volume".to_string(), Severity::Warning),
+            ]
+        );
+
+        // Daily series carry no volume
+        let mut daily = day(15, 1);
+        daily[0].volume = 0;
+        assert!(check(&daily, Timeframe::Daily).is_empty());
+    }
+
+    #[test]
+    fn price_and_volume_invariants() {
+        let mut candles = day(15, 5);
+        candles[0].high = dec!(150.25); // below close
+        candles[1].low = dec!(150.75); // above close
+        candles[2].low = dec!(0);
+        candles[3].volume = -5;
+        assert_eq!(
+            check(&candles, Timeframe::FiveMinute),
+            vec![
+                ("negative-volume".to_string(), Severity::Error),
+                ("non-positive-price".to_string(), Severity::Error),
+                ("ohlc".to_string(), Severity::Error),
+            ]
+        );
+
+        let issues = Validator::empty()
+            .with_rule(OhlcInvariants)
+            .check_day(&DayData {
+                symbol: "AAPL",
+                date: date(15),
+                timeframe: Timeframe::FiveMinute,
+                candles: &candles,
+            });
+        assert_eq!(
+            issues[0].message,
+            "2 candle(s) with high below open/close or low above open/close, \
+             first at 2025-01-15 14:30:00 UTC"
+        );
+    }
+
+    #[test]
+    fn bar_grid_sessions_and_file_dates() {
+        let mut candles = day(15, 3);
+        candles[0].timestamp += Duration::minutes(2);
+        // 20:30 ET, after the after-hours session
+        candles[1].timestamp = Utc.with_ymd_and_hms(2025, 1, 16, 1, 30, 0).unwrap();
+        candles.sort_by_key(|c| c.timestamp);
+        assert_eq!(
+            check(&candles, Timeframe::FiveMinute),
+            vec![
+                ("off-grid".to_string(), Severity::Warning),
+                ("outside-session".to_string(), Severity::Warning),
+                ("date-mismatch".to_string(), Severity::Error),
+            ]
+        );
+
+        // Daily bars belong at the exchange open, 13:30 UTC in summer
+        let summer = NaiveDate::from_ymd_opt(2025, 7, 15).unwrap();
+        let mut daily = day(15, 1);
+        daily[0].timestamp = Timeframe::session_open(summer);
+        fn daily_day(date: NaiveDate, candles: &[Candle]) -> DayData<'_> {
+            DayData {
+                symbol: "VIX",
+                date,
+                timeframe: Timeframe::Daily,
+                candles,
+            }
+        }
+        assert!(
+            Validator::default()
+                .check_day(&daily_day(summer, &daily))
+                .is_empty()
+        );
+        daily[0].timestamp = summer.and_hms_opt(14, 30, 0).unwrap().and_utc();
+        let issues = Validator::default().check_day(&daily_day(summer, &daily));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "off-grid");
+    }
+
+    struct MaxClose;
+
+    impl ValidationRule for MaxClose {
+        fn name(&self) -> &str {
+            "max-close"
+        }
+
+        fn check(&self, day: &DayData<'_>) -> Vec<Finding> {
+            day.candles
+                .iter()
+                .filter(|c| c.close > dec!(150))
+                .map(|c| Finding::info(format!("close {} at {}", c.close, c.timestamp)))
+                .take(1)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn validates_store_with_custom_rules() {
+        let backend = Arc::new(MemoryBackend::new());
+        let store = CandleStore::with_backend(backend.clone());
+        store.write_day("AAPL", date(14), &day(14, 78)).unwrap();
+        store.write_day("AAPL", date(15), &day(15, 78)).unwrap();
+        backend
+            .put(
+                &CandleStore::key("AAPL", date(16)),
+                Bytes::from_static(b"not parquet"),
+            )
+            .unwrap();
+
+        let validator = Validator::default().with_rule(MaxClose);
+        assert_eq!(validator.rule_names().first(), Some(&"empty"));
+        assert_eq!(validator.rule_names().last(), Some(&"max-close"));
+        let report = validator.validate(&store, &["AAPL"]).unwrap();
+        assert_eq!(report.days_checked, 3);
+        assert_eq!(report.count(Severity::Info), 2);
+        assert_eq!(report.count(Severity::Error), 1);
+        assert!(report.has_errors());
+        let unreadable = &report.issues[2];
+        assert_eq!(
+            (unreadable.rule.as_str(), unreadable.date),
+            ("read", date(16))
+        );
+        assert_eq!(
+            report.issues[0].to_string(),
+            "INFO: AAPL 2025-01-14: close 150.50 at 2025-01-14 14:30:00 UTC [max-close]"
+        );
+
+        let clean = Validator::empty().validate(&store, &["MSFT"]).unwrap();
+        assert!(clean.is_clean());
+        assert_eq!(clean.max_severity(), None);
+    }
+}
@@ -2,13 +2,14 @@ use std::collections::BTreeMap;
 
 use async_trait::async_trait;
 use chrono::{NaiveDate, TimeZone, Utc};
-use market_data_core::candle::Candle;
+use market_data_core::candle::{Candle, Timeframe};
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use tracing::{debug, info, warn};
 
 use crate::error::ProviderError;
+use crate::normalize::normalize_or_warn;
 use crate::provider::CandleProvider;
 
 const ALPACA_DATA_BASE_URL: &str = "https://data.alpaca.markets/v2";
@@ -171,6 +172,10 @@ struct AlpacaBar {
     c: Decimal,
     /// Volume
     v: i64,
+    /// Volume-weighted average price
+    vw: Option<Decimal>,
+    /// Trade count
+    n: Option<i64>,
 }
 
 impl AlpacaBar {
@@ -186,6 +191,8 @@ impl AlpacaBar {
             low: self.l,
             close: self.c,
             volume: self.v,
+            vwap: self.vw,
+            trade_count: self.n,
         })
     }
 }
@@ -224,12 +231,16 @@ impl CandleProvider for AlpacaProvider {
 
         let bars = self.fetch_bars_paginated(symbol, &start, &end).await?;
 
-        let mut candles: Vec<Candle> = bars
+        let candles: Vec<Candle> = bars
             .iter()
             .map(|b| b.to_candle())
             .collect::<Result<_, _>>()?;
-        candles.sort_by_key(|c| c.timestamp);
-        Ok(candles)
+        Ok(normalize_or_warn(
+            self.name(),
+            symbol,
+            candles,
+            Timeframe::FiveMinute,
+        ))
     }
 
     /// Fetch candles for a full date range in bulk using the Alpaca API.
@@ -259,6 +270,7 @@ impl CandleProvider for AlpacaProvider {
             .iter()
             .map(|b| b.to_candle())
             .collect::<Result<_, _>>()?;
+        let candles = normalize_or_warn(self.name(), symbol, candles, Timeframe::FiveMinute);
 
         Ok(group_candles_by_date(candles))
     }
@@ -278,6 +290,8 @@ mod tests {
             l: dec!(149.00),
             c: dec!(150.99),
             v: 1000,
+            vw: Some(dec!(150.4567)),
+            n: Some(42),
         };
 
         let candle = bar.to_candle().unwrap();
@@ -286,6 +300,8 @@ mod tests {
         assert_eq!(candle.low, dec!(149.00));
         assert_eq!(candle.close, dec!(150.99));
         assert_eq!(candle.volume, 1000);
+        assert_eq!(candle.vwap, Some(dec!(150.4567)));
+        assert_eq!(candle.trade_count, Some(42));
         assert_eq!(
             candle.timestamp,
             Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap()
@@ -296,7 +312,7 @@ mod tests {
     fn parse_alpaca_response_json() {
         let json = r#"{
             "bars": [
-                {"t": "2025-01-15T14:30:00Z", "o": 150.12, "h": 151.50, "l": 149.00, "c": 150.99, "v": 1000},
+                {"t": "2025-01-15T14:30:00Z", "o": 150.12, "h": 151.50, "l": 149.00, "c": 150.99, "v": 1000, "vw": 150.4567, "n": 42},
                 {"t": "2025-01-15T14:35:00Z", "o": 150.99, "h": 152.00, "l": 150.50, "c": 151.75, "v": 2000}
             ],
             "next_page_token": null
@@ -307,6 +323,11 @@ mod tests {
         assert_eq!(bars.len(), 2);
         assert_eq!(bars[0].v, 1000);
         assert_eq!(bars[1].v, 2000);
+        assert_eq!(bars[0].vw, Some(dec!(150.4567)));
+        assert_eq!(bars[0].n, Some(42));
+        // Older responses omit `vw` and `n`
+        assert_eq!(bars[1].vw, None);
+        assert_eq!(bars[1].n, None);
         assert!(response.next_page_token.is_none());
     }
 
@@ -340,6 +361,8 @@ mod tests {
                 low: dec!(149.00),
                 close: dec!(150.50),
                 volume: 1000,
+                vwap: None,
+                trade_count: None,
             },
             Candle {
                 timestamp: Utc.with_ymd_and_hms(2025, 1, 16, 14, 30, 0).unwrap(),
@@ -348,6 +371,8 @@ mod tests {
                 low: dec!(150.00),
                 close: dec!(151.50),
                 volume: 2000,
+                vwap: None,
+                trade_count: None,
             },
             Candle {
                 timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 14, 35, 0).unwrap(),
@@ -356,6 +381,8 @@ mod tests {
                 low: dec!(149.50),
                 close: dec!(151.00),
                 volume: 1500,
+                vwap: None,
+                trade_count: None,
             },
         ];
 
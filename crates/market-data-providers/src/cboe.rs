@@ -1,13 +1,14 @@
 use std::collections::BTreeMap;
 
 use async_trait::async_trait;
-use chrono::{NaiveDate, TimeZone, Utc};
-use market_data_core::candle::Candle;
+use chrono::NaiveDate;
+use market_data_core::candle::{Candle, Timeframe};
 use reqwest::Client;
 use rust_decimal::Decimal;
 use tracing::{debug, info};
 
 use crate::error::ProviderError;
+use crate::normalize::normalize_prices;
 use crate::provider::CandleProvider;
 
 const CBOE_BASE_URL: &str = "https://cdn.cboe.com/api/global/us_indices/daily_prices";
@@ -100,16 +101,19 @@ struct CboeRow {
 
 impl CboeRow {
     fn to_candle(&self) -> Candle {
-        // Use market open (14:30 UTC = 9:30 ET) as the timestamp for daily bars
-        let timestamp = Utc.from_utc_datetime(&self.date.and_hms_opt(14, 30, 0).unwrap());
-        Candle {
-            timestamp,
+        // Daily bars are labeled by the session open (9:30 ET) of their date
+        let mut candle = Candle {
+            timestamp: Timeframe::session_open(self.date),
             open: self.open,
             high: self.high,
             low: self.low,
             close: self.close,
             volume: 0, // CBOE daily data has no volume
-        }
+            vwap: None,
+            trade_count: None,
+        };
+        normalize_prices(&mut candle);
+        candle
     }
 }
 
@@ -207,6 +211,10 @@ impl CandleProvider for CboeProvider {
         "cboe"
     }
 
+    fn timeframe(&self) -> Timeframe {
+        Timeframe::Daily
+    }
+
     async fn fetch_candles(
         &self,
         symbol: &str,
@@ -250,6 +258,7 @@ impl CandleProvider for CboeProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{TimeZone, Utc};
     use rust_decimal_macros::dec;
 
     const SAMPLE_CSV: &str = "\
@@ -316,16 +325,26 @@ DATE,OPEN,HIGH,LOW,CLOSE
         };
 
         let candle = row.to_candle();
-        assert_eq!(candle.open, dec!(15.50));
+        assert_eq!(candle.open.to_string(), "15.5");
         assert_eq!(candle.high, dec!(16.75));
         assert_eq!(candle.low, dec!(14.25));
         assert_eq!(candle.close, dec!(15.82));
         assert_eq!(candle.volume, 0);
-        // Timestamp should be 14:30 UTC on the date
+        // Timestamp should be 9:30 ET on the date: 14:30 UTC in winter
         assert_eq!(
             candle.timestamp,
             Utc.with_ymd_and_hms(2025, 2, 21, 14, 30, 0).unwrap()
         );
+
+        // ...and 13:30 UTC under daylight saving time
+        let summer = CboeRow {
+            date: NaiveDate::from_ymd_opt(2025, 7, 15).unwrap(),
+            ..row
+        };
+        assert_eq!(
+            summer.to_candle().timestamp,
+            Utc.with_ymd_and_hms(2025, 7, 15, 13, 30, 0).unwrap()
+        );
     }
 
     #[test]
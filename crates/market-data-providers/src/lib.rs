@@ -1,5 +1,6 @@
 pub mod alpaca;
 pub mod cboe;
 pub mod error;
+pub mod normalize;
 pub mod provider;
 pub mod yahoo;
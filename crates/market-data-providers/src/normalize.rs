@@ -0,0 +1,165 @@
+use market_data_core::candle::{Candle, Timeframe};
+use rust_decimal::Decimal;
+use tracing::warn;
+
+/// Convert a price a source reports as a float to the decimal it stands for.
+///
+/// Float-sourced prices carry binary noise: Yahoo reports `150.12` as the
+/// single-precision value `150.11999511718750`. Values that are exactly
+/// representable as `f32` are read back at single precision, the rest at
+/// double precision, each as the shortest decimal that round-trips. Returns
+/// `None` for values that are not finite or do not fit a [`Decimal`].
+pub fn decimal_from_float(value: f64) -> Option<Decimal> {
+    let single = value as f32;
+    let text = if f64::from(single) == value {
+        single.to_string()
+    } else {
+        value.to_string()
+    };
+    text.parse::<Decimal>().ok().map(|d| d.normalize())
+}
+
+/// Drop trailing zeros from a candle's prices, so the same price is stored
+/// as the same string whichever source reported it.
+pub fn normalize_prices(candle: &mut Candle) {
+    candle.open = candle.open.normalize();
+    candle.high = candle.high.normalize();
+    candle.low = candle.low.normalize();
+    candle.close = candle.close.normalize();
+    candle.vwap = candle.vwap.map(|v| v.normalize());
+}
+
+/// Provider bars after normalizing to the bar-start convention.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizedBars {
+    /// Bars labeled by their start on the timeframe grid, sorted by timestamp.
+    pub candles: Vec<Candle>,
+    /// Intraday bars whose timestamp is not on the grid, such as a bar still
+    /// in progress stamped at the current minute. These are not stored.
+    pub off_grid: Vec<Candle>,
+}
+
+/// Normalize bars to the convention documented on [`Candle`]: each bar is
+/// labeled by its start, aligned to the timeframe grid in exchange time.
+/// Prices go through [`normalize_prices`].
+///
+/// Daily bars are re-stamped at the session open of their trading date, since
+/// the date is all a daily timestamp carries. Off-grid intraday bars cannot be
+/// attributed to a single slot and are split out into `off_grid`.
+pub fn normalize_bars(candles: Vec<Candle>, timeframe: Timeframe) -> NormalizedBars {
+    let mut normalized = NormalizedBars::default();
+    for mut candle in candles {
+        normalize_prices(&mut candle);
+        match timeframe {
+            Timeframe::Daily => {
+                candle.timestamp = timeframe.bar_start(&candle.timestamp);
+                normalized.candles.push(candle);
+            }
+            Timeframe::FiveMinute if candle.is_aligned(timeframe) => {
+                normalized.candles.push(candle);
+            }
+            Timeframe::FiveMinute => normalized.off_grid.push(candle),
+        }
+    }
+    normalized.candles.sort_by_key(|c| c.timestamp);
+    normalized
+}
+
+/// [`normalize_bars`], logging and dropping off-grid bars.
+pub(crate) fn normalize_or_warn(
+    provider: &str,
+    symbol: &str,
+    candles: Vec<Candle>,
+    timeframe: Timeframe,
+) -> Vec<Candle> {
+    let normalized = normalize_bars(candles, timeframe);
+    if !normalized.off_grid.is_empty() {
+        let stamps: Vec<String> = normalized
+            .off_grid
+            .iter()
+            .map(|c| c.timestamp.to_rfc3339())
+            .collect();
+        warn!(
+            "{symbol}: dropped {} off-grid {provider} bar(s): {}",
+            normalized.off_grid.len(),
+            stamps.join(", ")
+        );
+    }
+    normalized.candles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn candle(timestamp: DateTime<Utc>) -> Candle {
+        Candle {
+            timestamp,
+            open: dec!(150.00),
+            high: dec!(151.00),
+            low: dec!(149.00),
+            close: dec!(150.50),
+            volume: 1000,
+            vwap: None,
+            trade_count: None,
+        }
+    }
+
+    #[test]
+    fn splits_off_grid_intraday_bars() {
+        let last_bar = Utc.with_ymd_and_hms(2025, 1, 15, 20, 55, 0).unwrap();
+        let in_progress = Utc.with_ymd_and_hms(2025, 1, 15, 20, 58, 12).unwrap();
+        let first_bar = Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap();
+
+        let normalized = normalize_bars(
+            vec![candle(last_bar), candle(in_progress), candle(first_bar)],
+            Timeframe::FiveMinute,
+        );
+        assert_eq!(
+            normalized.candles,
+            vec![candle(first_bar), candle(last_bar)]
+        );
+        assert_eq!(normalized.off_grid, vec![candle(in_progress)]);
+    }
+
+    #[test]
+    fn float_prices_become_clean_decimals() {
+        assert_eq!(
+            decimal_from_float(f64::from(150.12_f32))
+                .unwrap()
+                .to_string(),
+            "150.12"
+        );
+        assert_eq!(
+            decimal_from_float(f64::from(3000.12_f32))
+                .unwrap()
+                .to_string(),
+            "3000.12"
+        );
+        assert_eq!(decimal_from_float(150.12).unwrap().to_string(), "150.12");
+        assert_eq!(decimal_from_float(0.0001).unwrap(), dec!(0.0001));
+        assert_eq!(decimal_from_float(17.0).unwrap().to_string(), "17");
+        assert!(decimal_from_float(f64::NAN).is_none());
+
+        let mut candle = candle(Utc.with_ymd_and_hms(2025, 1, 15, 14, 30, 0).unwrap());
+        candle.close = dec!(150.5000);
+        normalize_prices(&mut candle);
+        assert_eq!(candle.close.to_string(), "150.5");
+    }
+
+    #[test]
+    fn restamps_daily_bars_at_session_open() {
+        let date = NaiveDate::from_ymd_opt(2025, 7, 15).unwrap();
+        let normalized = normalize_bars(
+            vec![candle(Utc.with_ymd_and_hms(2025, 7, 15, 4, 0, 0).unwrap())],
+            Timeframe::Daily,
+        );
+        assert_eq!(
+            normalized.candles,
+            vec![candle(Timeframe::session_open(date))]
+        );
+        assert!(normalized.off_grid.is_empty());
+    }
+}
@@ -1,15 +1,24 @@
 use async_trait::async_trait;
 use chrono::{Datelike, NaiveDate};
-use market_data_core::candle::Candle;
+use market_data_core::candle::{Candle, Timeframe};
 
 use crate::error::ProviderError;
 
 /// Trait for fetching 5-minute candle data from an external source.
+///
+/// Candles are labeled by the start of their bar, aligned to the
+/// [`timeframe`](CandleProvider::timeframe) grid in exchange time. Bars a
+/// source reports off the grid are dropped; see [`crate::normalize`].
 #[async_trait]
 pub trait CandleProvider: Send + Sync {
     /// Provider name (for logging/display).
     fn name(&self) -> &str;
 
+    /// Bar interval of the candles this provider returns.
+    fn timeframe(&self) -> Timeframe {
+        Timeframe::FiveMinute
+    }
+
     /// Fetch 5-minute candles for a symbol on a specific date.
     /// Returns candles sorted by timestamp.
     /// Returns an empty vec if the date is not a trading day.
@@ -2,13 +2,14 @@ use std::collections::BTreeMap;
 
 use async_trait::async_trait;
 use chrono::{Datelike, NaiveDate, TimeZone, Utc};
-use market_data_core::candle::Candle;
+use market_data_core::candle::{Candle, Timeframe};
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use tracing::{debug, warn};
 
 use crate::error::ProviderError;
+use crate::normalize::{decimal_from_float, normalize_bars, normalize_or_warn};
 use crate::provider::CandleProvider;
 
 const YAHOO_CHART_URL: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
@@ -115,9 +116,9 @@ impl YahooProvider {
             return Ok(Vec::new());
         }
 
-        let mut candles = parse_yahoo_result(&results[0])?;
-        candles.sort_by_key(|c| c.timestamp);
-        Ok(candles)
+        // Daily bars are re-stamped at the session open of their date
+        let candles = parse_yahoo_result(&results[0])?;
+        Ok(normalize_bars(candles, Timeframe::Daily).candles)
     }
 }
 
@@ -159,7 +160,8 @@ struct YahooQuote {
 }
 
 fn f64_to_decimal(val: f64) -> Result<Decimal, ProviderError> {
-    Decimal::try_from(val).map_err(|e| ProviderError::Parse(format!("invalid decimal value: {e}")))
+    decimal_from_float(val)
+        .ok_or_else(|| ProviderError::Parse(format!("invalid decimal value: {val}")))
 }
 
 fn parse_yahoo_result(result: &YahooResult) -> Result<Vec<Candle>, ProviderError> {
@@ -206,6 +208,8 @@ fn parse_yahoo_result(result: &YahooResult) -> Result<Vec<Candle>, ProviderError
             low,
             close,
             volume,
+            vwap: None,
+            trade_count: None,
         });
     }
 
@@ -279,9 +283,14 @@ impl CandleProvider for YahooProvider {
             return Ok(Vec::new());
         }
 
-        let mut candles = parse_yahoo_result(&results[0])?;
-        candles.sort_by_key(|c| c.timestamp);
-        Ok(candles)
+        // The bar in progress is stamped at the request time; drop it
+        let candles = parse_yahoo_result(&results[0])?;
+        Ok(normalize_or_warn(
+            self.name(),
+            symbol,
+            candles,
+            Timeframe::FiveMinute,
+        ))
     }
 
     /// Override for Yahoo: fetch day by day with error tolerance and rate limiting.
@@ -440,13 +449,14 @@ mod tests {
         assert_eq!(candles[0].volume, 50000000);
         assert_eq!(candles[2].volume, 55000000);
         // Verify prices are parsed correctly
-        assert!(candles[0].close > dec!(378.0) && candles[0].close < dec!(379.0));
+        assert_eq!(candles[0].close, dec!(378.85));
     }
 
     #[test]
     fn f64_to_decimal_converts() {
-        let result = f64_to_decimal(150.12).unwrap();
-        // f64 -> Decimal may have precision nuances, but should be close
-        assert!(result > dec!(150.0) && result < dec!(151.0));
+        assert_eq!(f64_to_decimal(150.12).unwrap(), dec!(150.12));
+        // Yahoo's single-precision noise is dropped
+        assert_eq!(f64_to_decimal(f64::from(150.12_f32)).unwrap(), dec!(150.12));
+        assert!(f64_to_decimal(f64::INFINITY).is_err());
     }
 }